@@ -6,14 +6,19 @@ use quote::{format_ident, quote};
 use std::fs::read_dir;
 
 /// Looks in the puzzle director for all files matching `day_*.rs` and imports them as modules.
+/// Each day's type also gets a `DAY` const holding its number.
 #[proc_macro]
 pub fn import_solutions(_item: TokenStream) -> TokenStream {
     let imports = TokenStream2::from_iter(get_days().into_iter().map(|day| {
         let module_name = format_ident!("day_{:02}", day);
         let type_name = format_ident!("Day{:02}", day);
+        let day = day as u8;
         proc_macro2::TokenStream::from(quote! {
             mod #module_name;
             pub use #module_name::#type_name;
+            impl #type_name {
+                pub const DAY: u8 = #day;
+            }
         })
     }));
 
@@ -40,6 +45,15 @@ pub fn get_solution(item: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Expands to an array of every day with a solution file, e.g. `[1u8, 2u8, 3u8]`
+#[proc_macro]
+pub fn solution_days(_item: TokenStream) -> TokenStream {
+    let mut days = get_days();
+    days.sort();
+    let days = days.into_iter().map(|day| day as u8);
+    quote! { [#(#days),*] }.into()
+}
+
 fn get_days() -> Vec<u32> {
     read_dir("./src/puzzle")
         .unwrap()
@@ -62,3 +76,181 @@ fn parse_day_from_file(filename: &str) -> IResult<&str, u32> {
 
     Ok((input, day))
 }
+
+/// Implements `GridCell` for an enum from a `#[cell(...)]` attribute on every variant:
+/// - `#[cell('#')]` on a unit variant
+/// - `#[cell('^' => TravelDirection::N, 'v' => TravelDirection::S)]` on a variant with one
+///   field, giving the char for each value of that field
+/// - `#[cell(matching = char::is_ascii_alphanumeric)]` on a variant holding the char itself
+/// - `#[cell(render = visited_glyph)]` on variants never read from input, drawn by calling
+///   `visited_glyph(&cell)`
+///
+/// Characters no variant claims make `from_char` return `None`.
+///
+/// The impl names the trait as `::advent2024::GridCell`; put `#[grid_cell(crate = path)]` on
+/// the enum to find it somewhere else.
+#[proc_macro_derive(GridCell, attributes(cell, grid_cell))]
+pub fn derive_grid_cell(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as syn::DeriveInput);
+    match grid_cell_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+enum CellMapping {
+    Char(syn::LitChar),
+    Values(Vec<(syn::LitChar, syn::Path)>),
+    Matching(syn::Path),
+    Render(syn::Path),
+}
+
+impl syn::parse::Parse for CellMapping {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::Ident) {
+            let key: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            let path: syn::Path = input.parse()?;
+            return match key.to_string().as_str() {
+                "matching" => Ok(CellMapping::Matching(path)),
+                "render" => Ok(CellMapping::Render(path)),
+                _ => Err(syn::Error::new(
+                    key.span(),
+                    "expected `matching` or `render`",
+                )),
+            };
+        }
+        let mut c: syn::LitChar = input.parse()?;
+        if input.is_empty() {
+            return Ok(CellMapping::Char(c));
+        }
+        let mut values = Vec::new();
+        loop {
+            input.parse::<syn::Token![=>]>()?;
+            values.push((c, input.parse()?));
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            c = input.parse()?;
+        }
+        Ok(CellMapping::Values(values))
+    }
+}
+
+fn grid_cell_impl(input: &syn::DeriveInput) -> syn::Result<TokenStream2> {
+    let syn::Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "GridCell can only be derived for enums",
+        ));
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let krate = crate_path(&input.attrs)?;
+
+    let mut claimed: Vec<(char, syn::Ident)> = Vec::new();
+    let mut parse_arms = Vec::new();
+    // Guarded arms go last, so they can't shadow a variant's own char
+    let mut matching_arms = Vec::new();
+    let mut render_arms = Vec::new();
+    for variant in data.variants.iter() {
+        let ident = &variant.ident;
+        let attr = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("cell"))
+            .ok_or_else(|| syn::Error::new_spanned(ident, "missing #[cell(...)] attribute"))?;
+        let single_field =
+            matches!(&variant.fields, syn::Fields::Unnamed(f) if f.unnamed.len() == 1);
+
+        match (attr.parse_args::<CellMapping>()?, &variant.fields) {
+            (CellMapping::Char(c), syn::Fields::Unit) => {
+                claim(&mut claimed, &c, ident)?;
+                parse_arms.push(quote! { #c => Some(Self::#ident), });
+                render_arms.push(quote! { Self::#ident => #c, });
+            }
+            (CellMapping::Values(values), _) if single_field => {
+                for (c, value) in values.iter() {
+                    claim(&mut claimed, c, ident)?;
+                    parse_arms.push(quote! { #c => Some(Self::#ident(#value)), });
+                    render_arms.push(quote! { Self::#ident(#value) => #c, });
+                }
+                let message = format!("{}::{} has no char for this value", name, ident);
+                render_arms.push(quote! { Self::#ident(..) => unreachable!(#message), });
+            }
+            (CellMapping::Matching(predicate), _) if single_field => {
+                matching_arms.push(quote! { c if #predicate(&c) => Some(Self::#ident(c)), });
+                render_arms.push(quote! { Self::#ident(c) => *c, });
+            }
+            (CellMapping::Render(render), fields) => {
+                let pattern = match fields {
+                    syn::Fields::Unit => quote! { Self::#ident },
+                    syn::Fields::Unnamed(_) => quote! { Self::#ident(..) },
+                    syn::Fields::Named(_) => quote! { Self::#ident { .. } },
+                };
+                render_arms.push(quote! { #pattern => #render(self), });
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "this #[cell(...)] form doesn't fit the variant's fields",
+                ))
+            }
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics #krate::GridCell for #name #ty_generics #where_clause {
+            fn from_char(c: char) -> Option<Self> {
+                match c {
+                    #(#parse_arms)*
+                    #(#matching_arms)*
+                    _ => None,
+                }
+            }
+            // The catch-all for a variant's unlisted values is unreachable once all are listed
+            #[allow(unreachable_patterns)]
+            fn to_char(&self) -> char {
+                match self {
+                    #(#render_arms)*
+                }
+            }
+        }
+    })
+}
+
+/// The path from `#[grid_cell(crate = ...)]`, defaulting to `::advent2024`
+fn crate_path(attrs: &[syn::Attribute]) -> syn::Result<syn::Path> {
+    let mut krate = syn::parse_quote!(::advent2024);
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("grid_cell")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                krate = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `crate = path`"))
+            }
+        })?;
+    }
+    Ok(krate)
+}
+
+/// Fails if another variant already parses from `c`
+fn claim(
+    claimed: &mut Vec<(char, syn::Ident)>,
+    c: &syn::LitChar,
+    variant: &syn::Ident,
+) -> syn::Result<()> {
+    if let Some((_, other)) = claimed.iter().find(|(used, _)| *used == c.value()) {
+        return Err(syn::Error::new(
+            c.span(),
+            format!("{:?} is already the char for {}", c.value(), other),
+        ));
+    }
+    claimed.push((c.value(), variant.clone()));
+    Ok(())
+}
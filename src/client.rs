@@ -69,6 +69,7 @@ impl Client {
 				day: self.day,
 				part: self.part,
 				data: false,
+				threads: None,
 				command: None,
 			};
 			log::warn!("Since a new code-file was created, you may need to restart your language-server / restart your watch command!");
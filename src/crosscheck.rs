@@ -0,0 +1,207 @@
+//! Differential testing: runs a day's real solution against its slow-but-obvious reference
+//! implementation, on the day's examples then on generated inputs, until they disagree.
+
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use clap::Parser;
+
+use crate::generate::generated_input;
+use crate::puzzle::{run_part, PuzzleResult};
+use crate::{Puzzle, RootOpt};
+
+/// A brute-force solution for one part, given the same prepared input as the real one
+pub type ReferenceFn = fn(&str) -> PuzzleResult;
+
+/// An input where the solution and the reference give different answers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disagreement {
+	pub part: u8,
+	pub input: String,
+	pub solution: String,
+	pub reference: String,
+}
+impl fmt::Display for Disagreement {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Part {} disagrees on:", self.part)?;
+		writeln!(f, "{}", self.input)?;
+		writeln!(f, "  solution:  {}", self.solution)?;
+		write!(f, "  reference: {}", self.reference)
+	}
+}
+impl Disagreement {
+	/// How the solution & the reference each ended, in that order
+	pub fn endings(&self) -> (Ending, Ending) {
+		(Ending::of(&self.solution), Ending::of(&self.reference))
+	}
+}
+
+/// Whether a side of a comparison answered, returned an error or panicked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+	Answer,
+	Error,
+	Panic,
+}
+impl Ending {
+	/// Reads back the prefix `outcome` gave the rendered answer
+	fn of(outcome: &str) -> Self {
+		if outcome.starts_with("error: ") {
+			Ending::Error
+		} else if outcome.starts_with("panic: ") {
+			Ending::Panic
+		} else {
+			Ending::Answer
+		}
+	}
+}
+
+/// Renders an answer for comparison. Errors & panics count as answers too,
+/// so one side failing where the other doesn't is a disagreement.
+fn outcome(run: impl FnOnce() -> PuzzleResult) -> String {
+	match catch_unwind(AssertUnwindSafe(run)) {
+		Ok(Ok(answer)) => answer,
+		Ok(Err(e)) => format!("error: {}", e),
+		Err(panic) => {
+			let message = panic
+				.downcast_ref::<&str>()
+				.map(|s| s.to_string())
+				.or_else(|| panic.downcast_ref::<String>().cloned())
+				.unwrap_or_default();
+			format!("panic: {}", message)
+		}
+	}
+}
+
+/// Runs `part` of the puzzle & its reference on `input`, returning how they differ if they do
+pub fn compare(
+	puzzle: &dyn Puzzle,
+	part: u8,
+	input: &str,
+) -> Result<Option<Disagreement>, anyhow::Error> {
+	let Some(reference) = puzzle.reference(part) else {
+		anyhow::bail!("This day has no reference implementation for part {}", part);
+	};
+	let prepared = crate::input::prepare(puzzle, input)?;
+	let solution = outcome(|| run_part(puzzle, part, input));
+	let reference = outcome(|| reference(&prepared));
+	if solution == reference {
+		return Ok(None);
+	}
+	Ok(Some(Disagreement {
+		part,
+		input: prepared,
+		solution,
+		reference,
+	}))
+}
+
+/// Checks the examples, then `count` generated inputs from `seed` onwards. Returns the first
+/// disagreement found, or how many inputs agreed.
+pub fn first_disagreement(
+	puzzle: &dyn Puzzle,
+	part: u8,
+	size: usize,
+	seed: u64,
+	count: u64,
+) -> Result<Result<usize, Disagreement>, anyhow::Error> {
+	let mut checked = 0;
+	for example in puzzle.examples() {
+		if let Some(disagreement) = compare(puzzle, part, example.input)? {
+			return Ok(Err(disagreement));
+		}
+		checked += 1;
+	}
+	if puzzle.generator().is_some() {
+		for seed in seed..seed + count {
+			let input = generated_input(puzzle, size, seed)?;
+			if let Some(disagreement) = compare(puzzle, part, &input)? {
+				return Ok(Err(disagreement));
+			}
+			checked += 1;
+		}
+	}
+	Ok(Ok(checked))
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct CrosscheckCommand {
+	/// Day-specific size of each generated input
+	#[arg(short, long, default_value_t = 20)]
+	pub size: usize,
+
+	/// Seed for the first generated input
+	#[arg(long, default_value_t = 0)]
+	pub seed: u64,
+
+	/// How many generated inputs to check after the examples
+	#[arg(short, long, default_value_t = 100)]
+	pub count: u64,
+}
+
+impl CrosscheckCommand {
+	pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
+		log::info!("Running crosscheck command");
+		let day = crate::puzzle::solution(opt)?;
+
+		match first_disagreement(&*day, opt.part, self.size, self.seed, self.count)? {
+			Ok(checked) => {
+				println!("Solution & reference agree on {} inputs", checked);
+				Ok(())
+			}
+			Err(disagreement) => {
+				println!("{}", disagreement);
+				anyhow::bail!(
+					"Day {} part {} disagrees with its reference",
+					opt.day,
+					opt.part
+				)
+			}
+		}
+	}
+}
+
+#[test]
+fn solutions_agree_with_references() {
+	for day in macros::solution_days!() {
+		let puzzle = crate::puzzle::solution_for(day);
+		for part in [1, 2] {
+			if puzzle.reference(part).is_none() {
+				continue;
+			}
+			if let Err(disagreement) = first_disagreement(&*puzzle, part, 8, 0, 8).unwrap() {
+				panic!("day {:02} {}", day, disagreement);
+			}
+		}
+	}
+}
+
+#[test]
+fn compare_reports_differing_answers() {
+	struct Counter;
+	impl Puzzle for Counter {
+		fn new(_ops: &RootOpt) -> Box<dyn Puzzle> {
+			Box::new(Self)
+		}
+		fn part_one(&self, input: &str) -> PuzzleResult {
+			Ok(input.lines().count().to_string())
+		}
+		fn part_two(&self, _input: &str) -> PuzzleResult {
+			anyhow::bail!("not counting")
+		}
+		fn reference(&self, _part: u8) -> Option<ReferenceFn> {
+			Some(|input| Ok(input.split('\n').count().to_string()))
+		}
+	}
+
+	assert_eq!(compare(&Counter, 1, "a\nb").unwrap(), None);
+	assert_eq!(
+		compare(&Counter, 2, "a\nb").unwrap(),
+		Some(Disagreement {
+			part: 2,
+			input: "a\nb".to_string(),
+			solution: "error: not counting".to_string(),
+			reference: "2".to_string(),
+		})
+	);
+}
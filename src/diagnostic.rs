@@ -0,0 +1,256 @@
+//! Renders parser failures (chumsky or nom) as ariadne reports, carried up through
+//! `PuzzleResult` as ordinary errors so the runner can print them instead of the puzzle panicking.
+
+use std::fmt;
+
+use ariadne::{Color, Label, Report, ReportKind, Source};
+use chumsky::error::Rich;
+use nom_supreme::error::{ErrorTree, GenericErrorTree};
+
+/// A parse failure that has already been rendered for the terminal
+#[derive(Debug)]
+pub struct Diagnostic {
+	/// Name the report refers to the input by, e.g. `input/day05.txt`
+	pub file_name: String,
+	/// How many distinct errors the report contains
+	pub error_count: usize,
+	pub rendered: String,
+}
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.rendered)
+	}
+}
+impl std::error::Error for Diagnostic {}
+
+/// Where the runner keeps the input for a given day, used to label reports
+pub fn input_name(day: u8) -> String {
+	format!("input/day{:02}.txt", day)
+}
+
+impl Diagnostic {
+	/// Renders each of chumsky's errors as its own report against `source`
+	pub fn from_rich(file_name: &str, source: &str, errors: Vec<Rich<'_, char>>) -> Self {
+		let mut buffer: Vec<u8> = Vec::new();
+		for e in errors.iter() {
+			let span = char_offset(source, e.span().start)..char_offset(source, e.span().end);
+			Report::build(ReportKind::Error, file_name.to_string(), span.start)
+				.with_message(e.to_string())
+				.with_label(
+					Label::new((file_name.to_string(), span))
+						.with_message(e.reason().to_string())
+						.with_color(Color::Red),
+				)
+				.finish()
+				.write(source_cache(file_name, source), &mut buffer)
+				.expect("Couldn't render parse report");
+		}
+		Self::new(file_name, errors.len(), buffer)
+	}
+
+	/// Renders every failed branch of a nom-supreme error tree as its own report, pointing at
+	/// the offending input and labelling each enclosing `context` it was parsed within.
+	pub fn from_error_tree(file_name: &str, source: &str, error: &ErrorTree<&str>) -> Self {
+		let mut failures = Vec::new();
+		collect_failures(source, error, &Vec::new(), &mut failures);
+		Self::from_failures(file_name, source, failures)
+	}
+
+	/// As `from_error_tree`, also covering nom asking for more input than there was
+	pub fn from_nom(file_name: &str, source: &str, error: nom::Err<ErrorTree<&str>>) -> Self {
+		match error {
+			nom::Err::Error(tree) | nom::Err::Failure(tree) => {
+				Self::from_error_tree(file_name, source, &tree)
+			}
+			nom::Err::Incomplete(needed) => {
+				let failure = TreeFailure {
+					offset: source.len(),
+					message: format!("unexpected end of input ({:?})", needed),
+					contexts: Vec::new(),
+				};
+				Self::from_failures(file_name, source, vec![failure])
+			}
+		}
+	}
+
+	fn from_failures(file_name: &str, source: &str, failures: Vec<TreeFailure>) -> Self {
+		let mut buffer: Vec<u8> = Vec::new();
+		for failure in failures.iter() {
+			let span = char_span(source, failure.offset);
+			let mut report = Report::build(ReportKind::Error, file_name.to_string(), span.start)
+				.with_message(&failure.message)
+				.with_label(
+					Label::new((file_name.to_string(), span))
+						.with_message(&failure.message)
+						.with_color(Color::Red)
+						.with_order(0),
+				);
+			for (depth, (offset, context)) in failure.contexts.iter().enumerate() {
+				report = report.with_label(
+					Label::new((file_name.to_string(), char_span(source, *offset)))
+						.with_message(context)
+						.with_color(Color::Yellow)
+						.with_order(depth as i32 + 1),
+				);
+			}
+			report
+				.finish()
+				.write(source_cache(file_name, source), &mut buffer)
+				.expect("Couldn't render parse report");
+		}
+		Self::new(file_name, failures.len(), buffer)
+	}
+
+	fn new(file_name: &str, error_count: usize, buffer: Vec<u8>) -> Self {
+		Self {
+			file_name: file_name.to_string(),
+			error_count,
+			rendered: String::from_utf8_lossy(&buffer).into_owned(),
+		}
+	}
+}
+
+/// The only source any of our reports refer to. Writing into a `Vec` can't fail,
+/// so rendering errors are limited to the report naming some other file.
+fn source_cache(file_name: &str, source: &str) -> (String, Source) {
+	(file_name.to_string(), Source::from(source))
+}
+
+/// One leaf of a nom-supreme error tree, with the contexts that enclose it (innermost first)
+struct TreeFailure {
+	offset: usize,
+	message: String,
+	contexts: Vec<(usize, String)>,
+}
+
+fn collect_failures(
+	source: &str,
+	tree: &ErrorTree<&str>,
+	contexts: &Vec<(usize, String)>,
+	failures: &mut Vec<TreeFailure>,
+) {
+	match tree {
+		GenericErrorTree::Base { location, kind } => failures.push(TreeFailure {
+			offset: byte_offset(source, location),
+			message: kind.to_string(),
+			contexts: contexts.clone(),
+		}),
+		GenericErrorTree::Stack {
+			base,
+			contexts: stack,
+		} => {
+			// The stack is innermost first, and sits inside whatever contexts we already have
+			let mut nested = stack
+				.iter()
+				.map(|(location, context)| (byte_offset(source, location), context.to_string()))
+				.collect::<Vec<_>>();
+			nested.extend(contexts.iter().cloned());
+			collect_failures(source, base, &nested, failures);
+		}
+		GenericErrorTree::Alt(alternatives) => alternatives
+			.iter()
+			.for_each(|alt| collect_failures(source, alt, contexts, failures)),
+	}
+}
+
+/// nom hands back sub-slices of the original input, so their position is pointer arithmetic
+fn byte_offset(source: &str, location: &str) -> usize {
+	(location.as_ptr() as usize)
+		.checked_sub(source.as_ptr() as usize)
+		.filter(|offset| *offset <= source.len())
+		.unwrap_or(0)
+}
+
+/// Parsers speak in byte offsets, ariadne in chars
+fn char_offset(source: &str, byte_offset: usize) -> usize {
+	source
+		.get(..byte_offset)
+		.map_or(source.chars().count(), |prefix| prefix.chars().count())
+}
+
+/// Highlights the single character at `byte_offset`, or nothing at the end of the input
+fn char_span(source: &str, byte_offset: usize) -> std::ops::Range<usize> {
+	let start = char_offset(source, byte_offset);
+	let end = (start + 1).min(source.chars().count());
+	start..end
+}
+
+#[test]
+fn offsets_count_chars_not_bytes() {
+	let source = "ü€\nab€c";
+	assert_eq!(char_offset(source, 0), 0);
+	// 'c' starts 11 bytes in, but is only the 7th char
+	assert_eq!(char_offset(source, 11), 6);
+	assert_eq!(char_span(source, 11), 6..7);
+	assert_eq!(char_span(source, source.len()), 7..7);
+	assert_eq!(byte_offset(source, &source[11..]), 11);
+}
+
+#[test]
+fn reports_point_at_the_failing_line_and_column() {
+	use nom::Parser;
+	use nom_supreme::tag::complete::tag;
+
+	let source = "ü€\nab€c";
+	let error = nom::sequence::pair(tag("ü€\nab€"), tag("x"))
+		.parse(source)
+		.unwrap_err();
+	let diagnostic = Diagnostic::from_nom("input/day03.txt", source, error);
+	assert_eq!(diagnostic.error_count, 1);
+	assert!(
+		diagnostic.rendered.contains("input/day03.txt:2:4"),
+		"{}",
+		diagnostic.rendered
+	);
+
+	let errors = vec![Rich::custom((7..8).into(), "not a digit")];
+	let diagnostic = Diagnostic::from_rich("input/day05.txt", source, errors);
+	assert!(
+		diagnostic.rendered.contains("input/day05.txt:2:2"),
+		"{}",
+		diagnostic.rendered
+	);
+}
+
+#[test]
+fn reports_name_the_enclosing_contexts() {
+	use nom::Parser;
+	use nom_supreme::tag::complete::tag;
+	use nom_supreme::ParserExt;
+
+	let source = "mul(2,x)";
+	let mut parser = nom::sequence::pair(
+		tag("mul("),
+		nom::sequence::pair(
+			tag("2,").context("first number"),
+			tag("3").context("second number"),
+		),
+	)
+	.context("call");
+	let Err(nom::Err::Error(tree)) = parser.parse(source) else {
+		panic!("the parse should fail");
+	};
+
+	let mut failures = Vec::new();
+	collect_failures(source, &tree, &Vec::new(), &mut failures);
+	assert_eq!(failures.len(), 1);
+	assert_eq!(failures[0].offset, 6);
+	// Innermost first
+	assert_eq!(
+		failures[0].contexts,
+		vec![
+			(6, "in section \"second number\"".to_string()),
+			(0, "in section \"call\"".to_string())
+		]
+	);
+
+	let diagnostic = Diagnostic::from_nom("input/day03.txt", source, nom::Err::Error(tree));
+	for context in ["second number", "call"] {
+		assert!(
+			diagnostic.rendered.contains(context),
+			"{}",
+			diagnostic.rendered
+		);
+	}
+	assert!(!diagnostic.rendered.contains("first number"));
+}
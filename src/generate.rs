@@ -0,0 +1,90 @@
+//! Random-but-valid puzzle inputs, for stress testing beyond the examples & our own input
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{Puzzle, RootOpt};
+
+/// Implemented by days that can invent their own inputs
+pub trait InputGenerator {
+	/// An input that passes the day's validations. What `size` measures (grid side, number of
+	/// equations, ...) is up to the day; the same `rng` state must always give the same text.
+	fn generate(&self, size: usize, rng: &mut StdRng) -> String;
+}
+
+/// Generates inputs for `size` & `seed`, checking each passes the day's own validations
+pub fn generated_input(
+	puzzle: &dyn Puzzle,
+	size: usize,
+	seed: u64,
+) -> Result<String, anyhow::Error> {
+	let Some(generator) = puzzle.generator() else {
+		anyhow::bail!("This day has no input generator");
+	};
+	let text = generator.generate(size, &mut StdRng::seed_from_u64(seed));
+	crate::input::prepare(puzzle, &text)
+		.map_err(|e| anyhow::anyhow!("Generated input (seed {}) fails validation: {}", seed, e))?;
+	Ok(text)
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct GenerateCommand {
+	/// Day-specific size of each input (grid side length, number of equations, ...)
+	#[arg(short, long, default_value_t = 20)]
+	pub size: usize,
+
+	/// Seed for the first input, each further input uses the next seed
+	#[arg(long, default_value_t = 0)]
+	pub seed: u64,
+
+	/// How many inputs to write
+	#[arg(short, long, default_value_t = 1)]
+	pub count: u64,
+
+	/// Folder to write the inputs to
+	#[arg(short, long, default_value = "./input/generated")]
+	pub out: PathBuf,
+}
+
+impl GenerateCommand {
+	pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
+		log::info!("Running generate command");
+		let day = crate::puzzle::solution(opt)?;
+
+		std::fs::create_dir_all(&self.out)?;
+		for seed in self.seed..self.seed + self.count {
+			let text = generated_input(&*day, self.size, seed)?;
+			let path = self.out.join(format!(
+				"day{:02}_size{}_seed{}.txt",
+				opt.day, self.size, seed
+			));
+			std::fs::write(&path, text)?;
+			println!("Wrote {}", path.display());
+		}
+		Ok(())
+	}
+}
+
+#[test]
+fn generated_inputs_are_repeatable_and_solvable() {
+	for day in macros::solution_days!() {
+		let puzzle = crate::puzzle::solution_for(day);
+		if puzzle.generator().is_none() {
+			continue;
+		}
+		for seed in 0..4 {
+			let text = generated_input(&*puzzle, 8, seed).unwrap();
+			assert_eq!(text, generated_input(&*puzzle, 8, seed).unwrap());
+			for part in [1, 2] {
+				if let Err(e) = crate::puzzle::run_part(&*puzzle, part, &text) {
+					panic!(
+						"day {:02} part {} seed {}: {:?}\n{}",
+						day, part, seed, e, text
+					);
+				}
+			}
+		}
+	}
+}
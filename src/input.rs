@@ -0,0 +1,209 @@
+//! Cleans up raw puzzle input, and checks it against a puzzle's declared expectations,
+//! before any puzzle code gets to see it.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::Puzzle;
+
+/// How much surrounding whitespace the runner strips from the input
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TrimPolicy {
+	/// Leave the text exactly as it arrived (after line-ending cleanup)
+	None,
+	/// Strip only the blank lines at the very end of the input
+	End,
+	/// Strip trailing whitespace on every line and blank lines at the start and end
+	#[default]
+	Lines,
+}
+
+/// A check the runner performs on the normalized input before solving
+#[derive(Debug, Clone, PartialEq)]
+pub enum Validation {
+	/// Every line has the same number of characters
+	RectangularGrid,
+	/// Only these characters may appear (newlines are always allowed)
+	AllowedChars(&'static str),
+	/// Every integer in the input (with an optional leading `-`) falls in this range
+	NumberRange(RangeInclusive<i64>),
+}
+
+/// Describes where the input broke a puzzle's expectations. Lines & columns are 1-based.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputError {
+	pub line: usize,
+	pub column: usize,
+	pub message: String,
+}
+impl InputError {
+	pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+		Self {
+			line,
+			column,
+			message: message.into(),
+		}
+	}
+}
+impl fmt::Display for InputError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"line {}, column {}: {}",
+			self.line, self.column, self.message
+		)
+	}
+}
+impl std::error::Error for InputError {}
+
+/// Strips a byte-order-mark, converts CRLF (and lone CR) to LF, then trims per `policy`
+pub fn normalize(input: &str, policy: TrimPolicy) -> String {
+	let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+	let unified = input.replace("\r\n", "\n").replace('\r', "\n");
+
+	match policy {
+		TrimPolicy::None => unified,
+		TrimPolicy::End => unified.trim_end_matches('\n').to_string(),
+		TrimPolicy::Lines => {
+			let lines = unified.lines().map(str::trim_end).collect::<Vec<_>>();
+			let first = lines.iter().position(|l| !l.is_empty());
+			let last = lines.iter().rposition(|l| !l.is_empty());
+			match (first, last) {
+				(Some(first), Some(last)) => lines[first..=last].join("\n"),
+				_ => String::new(),
+			}
+		}
+	}
+}
+
+impl Validation {
+	pub fn check(&self, input: &str) -> Result<(), InputError> {
+		match self {
+			Validation::RectangularGrid => check_rectangular(input),
+			Validation::AllowedChars(allowed) => check_allowed_chars(input, allowed),
+			Validation::NumberRange(range) => check_number_range(input, range),
+		}
+	}
+}
+
+fn check_rectangular(input: &str) -> Result<(), InputError> {
+	let mut lines = input.lines().enumerate();
+	let Some((_, first)) = lines.next() else {
+		return Err(InputError::new(
+			1,
+			1,
+			"expected a grid but the input is empty",
+		));
+	};
+	let width = first.chars().count();
+	for (idx, line) in lines {
+		let len = line.chars().count();
+		if len != width {
+			return Err(InputError::new(
+				idx + 1,
+				len.min(width) + 1,
+				format!(
+					"expected {} columns like the first row, found {}",
+					width, len
+				),
+			));
+		}
+	}
+	Ok(())
+}
+
+fn check_allowed_chars(input: &str, allowed: &str) -> Result<(), InputError> {
+	for (idx, line) in input.lines().enumerate() {
+		if let Some((col, c)) = line
+			.chars()
+			.enumerate()
+			.find(|(_, c)| !allowed.contains(*c))
+		{
+			return Err(InputError::new(
+				idx + 1,
+				col + 1,
+				format!("unexpected character {:?}, allowed: {:?}", c, allowed),
+			));
+		}
+	}
+	Ok(())
+}
+
+fn check_number_range(input: &str, range: &RangeInclusive<i64>) -> Result<(), InputError> {
+	for (idx, line) in input.lines().enumerate() {
+		let chars = line.chars().collect::<Vec<_>>();
+		let mut col = 0;
+		while col < chars.len() {
+			let negative =
+				chars[col] == '-' && chars.get(col + 1).is_some_and(char::is_ascii_digit);
+			if !negative && !chars[col].is_ascii_digit() {
+				col += 1;
+				continue;
+			}
+			let start = col;
+			col += 1;
+			while col < chars.len() && chars[col].is_ascii_digit() {
+				col += 1;
+			}
+			let text = chars[start..col].iter().collect::<String>();
+			let in_range = text.parse::<i64>().is_ok_and(|n| range.contains(&n));
+			if !in_range {
+				return Err(InputError::new(
+					idx + 1,
+					start + 1,
+					format!(
+						"number {} is outside the expected range {}..={}",
+						text,
+						range.start(),
+						range.end()
+					),
+				));
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Normalizes `input` per the puzzle's policy, then runs every declared validation.
+pub fn prepare(puzzle: &dyn Puzzle, input: &str) -> Result<String, InputError> {
+	let normalized = normalize(input, puzzle.trim_policy());
+	for validation in puzzle.validations() {
+		validation.check(&normalized)?;
+	}
+	Ok(normalized)
+}
+
+#[test]
+fn normalize_line_endings_and_bom() {
+	let input = "\u{feff}\r\n1 2  \r\n3 4\r\n\r\n";
+	assert_eq!(normalize(input, TrimPolicy::Lines), "1 2\n3 4");
+	assert_eq!(normalize(input, TrimPolicy::End), "\n1 2  \n3 4");
+	assert_eq!(normalize(input, TrimPolicy::None), "\n1 2  \n3 4\n\n");
+}
+
+#[test]
+fn validations_report_line_and_column() {
+	assert_eq!(
+		Validation::RectangularGrid.check("...\n..\n..."),
+		Err(InputError::new(
+			2,
+			3,
+			"expected 3 columns like the first row, found 2"
+		))
+	);
+	assert_eq!(
+		Validation::AllowedChars(".#")
+			.check("..#\n.x.")
+			.map_err(|e| (e.line, e.column)),
+		Err((2, 2))
+	);
+	assert_eq!(
+		Validation::NumberRange(0..=99)
+			.check("1 2\n3 -4")
+			.map_err(|e| (e.line, e.column)),
+		Err((2, 3))
+	);
+	assert!(Validation::NumberRange(0..=99)
+		.check("12|99\n1,2,3")
+		.is_ok());
+}
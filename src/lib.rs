@@ -1,12 +1,31 @@
+// Lets `::advent2024::...` paths from the derive macros resolve inside this crate too
+extern crate self as advent2024;
+
 mod client;
+mod crosscheck;
+mod diagnostic;
+mod generate;
+mod input;
+pub mod line;
+mod minimize;
+pub mod pathfinding;
+pub mod pattern;
 pub mod prelude;
 mod puzzle;
+pub mod region;
+pub mod sparse_grid;
+#[cfg(test)]
+mod testing;
+pub mod transform;
 
 pub use puzzle::Puzzle;
 
 use clap::Parser;
 use client::DownloadCommand;
 use client::SubmitCommand;
+use crosscheck::CrosscheckCommand;
+use generate::GenerateCommand;
+use minimize::MinimizeCommand;
 use puzzle::PuzzleCommand;
 
 #[derive(Parser, Debug, Clone)]
@@ -27,6 +46,10 @@ pub struct RootOpt {
 	#[arg(long)]
 	pub data: bool,
 
+	/// Threads for puzzles that parallelise (default: one per core)
+	#[arg(long)]
+	pub threads: Option<usize>,
+
 	#[command(subcommand)]
 	command: Option<Commands>,
 }
@@ -36,9 +59,19 @@ enum Commands {
 	Puzzle(puzzle::PuzzleCommand),
 	Download(DownloadCommand),
 	Submit(SubmitCommand),
+	Generate(GenerateCommand),
+	Crosscheck(CrosscheckCommand),
+	Minimize(MinimizeCommand),
 }
 
 impl RootOpt {
+	/// How many threads a puzzle should spread its work over
+	pub fn threads(&self) -> usize {
+		self.threads
+			.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+			.max(1)
+	}
+
 	pub fn run(&self) -> Result<(), anyhow::Error> {
 		log::info!("Running day {} part {}", self.day, self.part);
 
@@ -58,6 +91,9 @@ impl Commands {
 			Commands::Download(cmd) => cmd.run(opt),
 			Commands::Submit(cmd) => cmd.run(opt),
 			Commands::Puzzle(cmd) => cmd.run(opt),
+			Commands::Generate(cmd) => cmd.run(opt),
+			Commands::Crosscheck(cmd) => cmd.run(opt),
+			Commands::Minimize(cmd) => cmd.run(opt),
 		}
 	}
 }
@@ -70,35 +106,114 @@ pub type GridCoord2 = (usize, usize);
 #[derive(Clone, Copy, Debug, Default)]
 pub struct GridDistance2<T>(T, T);
 impl GridDistance2<i32> {
-	pub fn normalize(&self) -> GridDistance2<i32>
-// where
-	// 	T: Sized
-	// 		+ From<T>
-	// 		+ From<std::num::NonZero<T>>
-	// 		+ Copy
-	// 		+ std::ops::Div<Output = T>
-	// 		+ std::ops::Rem<Output = T>
-	// 		+ std::cmp::PartialOrd,
-	{
-		if self.0 <= self.1 {
-			if self.1 % self.0 == 0 {
-				return GridDistance2(self.0 / self.0, self.1 / self.0);
-			} else {
-				return *self;
-			}
-		} else {
-			if self.0 % self.1 == 0 {
-				return GridDistance2(self.0 / self.1, self.1 / self.1);
-			}
+	/// The smallest lattice step in the same direction, e.g. (4, -6) becomes (2, -3).
+	/// Signs are kept, so it still points from the first coord towards the second.
+	pub fn normalize(&self) -> GridDistance2<i32> {
+		let divisor = gcd(self.0.unsigned_abs(), self.1.unsigned_abs()) as i32;
+		if divisor == 0 {
 			return *self;
 		}
+		GridDistance2(self.0 / divisor, self.1 / divisor)
+	}
+	pub fn manhattan(&self) -> u64 {
+		Vec2::from(*self).manhattan()
+	}
+	pub fn chebyshev(&self) -> u64 {
+		Vec2::from(*self).chebyshev()
 	}
+	pub fn euclidean(&self) -> f64 {
+		Vec2::from(*self).euclidean()
+	}
+}
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+	while b != 0 {
+		(a, b) = (b, a % b);
+	}
+	a
 }
+
+/// A signed (y, x) vector, for positions & offsets that may step off the grid.
+/// Convert back to a `GridCoord2` with `FBGrid::coord` once it's known to be on the grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2 {
+	pub y: i64,
+	pub x: i64,
+}
+impl Vec2 {
+	pub const fn new(y: i64, x: i64) -> Self {
+		Self { y, x }
+	}
+	/// Steps needed moving only orthogonally
+	pub fn manhattan(&self) -> u64 {
+		self.y.unsigned_abs() + self.x.unsigned_abs()
+	}
+	/// Steps needed when diagonal moves are allowed too
+	pub fn chebyshev(&self) -> u64 {
+		self.y.unsigned_abs().max(self.x.unsigned_abs())
+	}
+	pub fn euclidean(&self) -> f64 {
+		(self.y as f64).hypot(self.x as f64)
+	}
+}
+impl From<GridCoord2> for Vec2 {
+	fn from((y, x): GridCoord2) -> Self {
+		Self::new(y as i64, x as i64)
+	}
+}
+impl From<GridDistance2<i32>> for Vec2 {
+	fn from(GridDistance2(y, x): GridDistance2<i32>) -> Self {
+		Self::new(y.into(), x.into())
+	}
+}
+/// Fails for negative components; any upper bound depends on the grid
+impl TryFrom<Vec2> for GridCoord2 {
+	type Error = std::num::TryFromIntError;
+	fn try_from(v: Vec2) -> Result<Self, Self::Error> {
+		Ok((usize::try_from(v.y)?, usize::try_from(v.x)?))
+	}
+}
+impl std::ops::Add for Vec2 {
+	type Output = Self;
+	fn add(self, rhs: Self) -> Self {
+		Self::new(self.y + rhs.y, self.x + rhs.x)
+	}
+}
+impl std::ops::Sub for Vec2 {
+	type Output = Self;
+	fn sub(self, rhs: Self) -> Self {
+		Self::new(self.y - rhs.y, self.x - rhs.x)
+	}
+}
+impl std::ops::Neg for Vec2 {
+	type Output = Self;
+	fn neg(self) -> Self {
+		Self::new(-self.y, -self.x)
+	}
+}
+impl std::ops::Mul<i64> for Vec2 {
+	type Output = Self;
+	fn mul(self, rhs: i64) -> Self {
+		Self::new(self.y * rhs, self.x * rhs)
+	}
+}
+impl std::ops::AddAssign for Vec2 {
+	fn add_assign(&mut self, rhs: Self) {
+		*self = *self + rhs;
+	}
+}
+impl std::ops::SubAssign for Vec2 {
+	fn sub_assign(&mut self, rhs: Self) {
+		*self = *self - rhs;
+	}
+}
+
 #[derive(
 	Debug,
 	Clone,
 	Copy,
 	PartialEq,
+	Eq,
+	Hash,
 	strum_macros::EnumString,
 	strum_macros::Display,
 	strum_macros::EnumIter,
@@ -114,8 +229,19 @@ pub enum TravelDirection {
 	NW,
 }
 impl TravelDirection {
+	/// Every direction, clockwise from N as the variants are declared
+	const ALL: [TravelDirection; 8] = [
+		TravelDirection::N,
+		TravelDirection::NE,
+		TravelDirection::E,
+		TravelDirection::SE,
+		TravelDirection::S,
+		TravelDirection::SW,
+		TravelDirection::W,
+		TravelDirection::NW,
+	];
 	pub fn all() -> Vec<TravelDirection> {
-		TravelDirection::iter().collect()
+		Vec::from(Self::ALL)
 	}
 	pub fn cardinal() -> Vec<TravelDirection> {
 		const CARDINAL_PLAN: [TravelDirection; 4] = [
@@ -149,6 +275,70 @@ impl TravelDirection {
 		}
 	}
 
+	pub fn lt90(&self) -> TravelDirection {
+		self.turned(6)
+	}
+	pub fn rt45(&self) -> TravelDirection {
+		self.turned(1)
+	}
+	pub fn lt45(&self) -> TravelDirection {
+		self.turned(7)
+	}
+	pub fn opposite(&self) -> TravelDirection {
+		self.turned(4)
+	}
+	/// Clockwise by `eighths` of a turn
+	fn turned(&self, eighths: usize) -> TravelDirection {
+		Self::ALL[(*self as usize + eighths) % Self::ALL.len()]
+	}
+
+	/// One step this way, with N being up the grid
+	pub fn delta(&self) -> Vec2 {
+		match self {
+			Self::N => Vec2::new(-1, 0),
+			Self::NE => Vec2::new(-1, 1),
+			Self::E => Vec2::new(0, 1),
+			Self::SE => Vec2::new(1, 1),
+			Self::S => Vec2::new(1, 0),
+			Self::SW => Vec2::new(1, -1),
+			Self::W => Vec2::new(0, -1),
+			Self::NW => Vec2::new(-1, -1),
+		}
+	}
+
+	/// `^ > v <`, with unicode arrows for the diagonals
+	pub fn arrow(&self) -> char {
+		match self {
+			Self::N => '^',
+			Self::NE => '↗',
+			Self::E => '>',
+			Self::SE => '↘',
+			Self::S => 'v',
+			Self::SW => '↙',
+			Self::W => '<',
+			Self::NW => '↖',
+		}
+	}
+	pub fn from_arrow(c: char) -> Option<TravelDirection> {
+		Self::iter().find(|dir| dir.arrow() == c)
+	}
+	/// `U D L R`, as used by move lists
+	pub fn from_udlr(c: char) -> Option<TravelDirection> {
+		match c {
+			'U' => Some(Self::N),
+			'R' => Some(Self::E),
+			'D' => Some(Self::S),
+			'L' => Some(Self::W),
+			_ => None,
+		}
+	}
+	/// A single compass letter. The diagonals need two, so parse those with `FromStr`.
+	pub fn from_compass(c: char) -> Option<TravelDirection> {
+		Self::cardinal()
+			.into_iter()
+			.find(|dir| dir.to_string().starts_with(c))
+	}
+
 	pub fn next_coord(&self, coord: GridCoord2) -> Option<GridCoord2> {
 		self.next_coord_with_dist(coord, 1)
 	}
@@ -166,6 +356,100 @@ impl TravelDirection {
 	}
 }
 
+/// Any subset of the eight `TravelDirection`s, as one bit each
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DirectionSet(u8);
+impl DirectionSet {
+	fn bit(dir: TravelDirection) -> u8 {
+		1 << dir as u8
+	}
+	/// Whether `dir` is new to the set
+	pub fn insert(&mut self, dir: TravelDirection) -> bool {
+		let new = !self.contains(dir);
+		self.0 |= Self::bit(dir);
+		new
+	}
+	/// Whether `dir` was in the set
+	pub fn remove(&mut self, dir: TravelDirection) -> bool {
+		let had = self.contains(dir);
+		self.0 &= !Self::bit(dir);
+		had
+	}
+	pub fn contains(&self, dir: TravelDirection) -> bool {
+		self.0 & Self::bit(dir) != 0
+	}
+	pub fn union(&self, other: DirectionSet) -> DirectionSet {
+		DirectionSet(self.0 | other.0)
+	}
+	pub fn intersection(&self, other: DirectionSet) -> DirectionSet {
+		DirectionSet(self.0 & other.0)
+	}
+	pub fn is_empty(&self) -> bool {
+		self.0 == 0
+	}
+	pub fn len(&self) -> usize {
+		self.0.count_ones() as usize
+	}
+	/// Clockwise from N
+	pub fn iter(&self) -> impl Iterator<Item = TravelDirection> {
+		let set = *self;
+		TravelDirection::iter().filter(move |dir| set.contains(*dir))
+	}
+
+	/// Draws the set as the arms of a path through a cell: box-drawing lines out towards
+	/// each cardinal direction, so a path entered heading N and left heading E is the
+	/// set `{S, E}` and draws as '┌'. A lone arm draws as an arrow, and sets mixing in
+	/// diagonals fall back to a star.
+	pub fn glyph(&self) -> char {
+		use TravelDirection::*;
+		if !self
+			.intersection(TravelDirection::ordinal().into_iter().collect())
+			.is_empty()
+		{
+			return match self.iter().collect::<Vec<_>>()[..] {
+				[dir] => dir.arrow(),
+				[NE, SW] => '⤢',
+				[SE, NW] => '⤡',
+				_ => '✱',
+			};
+		}
+		match (
+			self.contains(N),
+			self.contains(E),
+			self.contains(S),
+			self.contains(W),
+		) {
+			(true, true, true, true) => '┼',
+			(true, true, true, false) => '├',
+			(true, true, false, true) => '┴',
+			(true, true, false, false) => '└',
+			(true, false, true, true) => '┤',
+			(true, false, true, false) => '↕',
+			(true, false, false, true) => '┘',
+			(true, false, false, false) => '↑',
+			(false, true, true, true) => '┬',
+			(false, true, true, false) => '┌',
+			(false, true, false, true) => '↔',
+			(false, true, false, false) => '→',
+			(false, false, true, true) => '┐',
+			(false, false, true, false) => '↓',
+			(false, false, false, true) => '←',
+			(false, false, false, false) => '.',
+		}
+	}
+}
+impl From<TravelDirection> for DirectionSet {
+	fn from(dir: TravelDirection) -> Self {
+		DirectionSet(Self::bit(dir))
+	}
+}
+impl FromIterator<TravelDirection> for DirectionSet {
+	fn from_iter<I: IntoIterator<Item = TravelDirection>>(iter: I) -> Self {
+		iter.into_iter()
+			.fold(DirectionSet::default(), |set, dir| set.union(dir.into()))
+	}
+}
+
 use grid::Grid;
 
 pub fn grid_from_vec_vec<I, O>(data: Vec<Vec<I>>) -> Grid<O>
@@ -182,17 +466,221 @@ where
 	grid
 }
 
+/// A grid cell that's written as a single character in puzzle input
+pub trait GridCell: Sized {
+	/// `None` for characters that aren't a valid cell
+	fn from_char(c: char) -> Option<Self>;
+	fn to_char(&self) -> char;
+}
+
+/// What happens to coordinates that step off an edge of an `FBGrid`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Edges {
+	/// They're off the grid
+	#[default]
+	Bounded,
+	/// They come back in on the opposite edge, as on a torus
+	Wrapping,
+}
+
 #[derive(Clone)]
 pub struct FBGrid<T>
 where
 	T: Default + PartialEq,
 {
 	grid: Grid<T>,
+	edges: Edges,
+}
+impl<T> From<Grid<T>> for FBGrid<T>
+where
+	T: Default + PartialEq,
+{
+	fn from(grid: Grid<T>) -> Self {
+		Self {
+			grid,
+			edges: Edges::default(),
+		}
+	}
+}
+impl<T> FBGrid<T>
+where
+	T: GridCell + Default + PartialEq,
+{
+	/// One cell per character and one row per line, skipping blank lines.
+	/// Every row must be as wide as the first.
+	pub fn parse(input: &str) -> Result<Self, input::InputError> {
+		Self::parse_with(input, T::from_char)
+	}
+}
+impl<T> FBGrid<T>
+where
+	T: Default + PartialEq,
+{
+	/// Like `parse`, but reading each character with `cell` rather than `GridCell`
+	pub fn parse_with(
+		input: &str,
+		cell: impl Fn(char) -> Option<T>,
+	) -> Result<Self, input::InputError> {
+		let mut rows: Vec<Vec<T>> = Vec::new();
+		let lines = input
+			.lines()
+			.enumerate()
+			.filter(|(_, l)| !l.trim().is_empty());
+		for (idx, line) in lines {
+			let row = line
+				.chars()
+				.enumerate()
+				.map(|(col, c)| {
+					cell(c).ok_or_else(|| {
+						input::InputError::new(idx + 1, col + 1, format!("unexpected cell {:?}", c))
+					})
+				})
+				.collect::<Result<Vec<T>, _>>()?;
+			if let Some(first) = rows.first() {
+				if first.len() != row.len() {
+					return Err(input::InputError::new(
+						idx + 1,
+						row.len().min(first.len()) + 1,
+						format!(
+							"expected {} columns like the first row, found {}",
+							first.len(),
+							row.len()
+						),
+					));
+				}
+			}
+			rows.push(row);
+		}
+		let Some(width) = rows.first().map(Vec::len) else {
+			return Err(input::InputError::new(
+				1,
+				1,
+				"expected a grid but the input is empty",
+			));
+		};
+		Ok(Self::from(Grid::from_vec(
+			rows.into_iter().flatten().collect(),
+			width,
+		)))
+	}
+}
+/// Renders in the input's layout, with a newline after every row
+impl<T> std::fmt::Display for FBGrid<T>
+where
+	T: GridCell + Default + PartialEq,
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for row in self.grid.iter_rows() {
+			writeln!(f, "{}", row.map(GridCell::to_char).collect::<String>())?;
+		}
+		Ok(())
+	}
 }
 impl<CellType> FBGrid<CellType>
 where
 	CellType: Default + PartialEq,
 {
+	/// Every cell equal to `needle`, with its coordinate
+	pub fn find_iter(&self, needle: CellType) -> impl Iterator<Item = (GridCoord2, &CellType)> {
+		self.grid
+			.indexed_iter()
+			.filter(move |(_, val)| **val == needle)
+	}
+	pub fn with_edges(mut self, edges: Edges) -> Self {
+		self.edges = edges;
+		self
+	}
+	pub fn edges(&self) -> Edges {
+		self.edges
+	}
+	/// The grid coordinate `pos` lands on: if it's within the grid when `Bounded`,
+	/// or after wrapping around the edges when `Wrapping`
+	pub fn coord(&self, pos: Vec2) -> Option<GridCoord2> {
+		let (rows, cols) = (self.grid.rows(), self.grid.cols());
+		if self.edges == Edges::Wrapping && rows > 0 && cols > 0 {
+			return Some((
+				pos.y.rem_euclid(rows as i64) as usize,
+				pos.x.rem_euclid(cols as i64) as usize,
+			));
+		}
+		let coord = GridCoord2::try_from(pos).ok()?;
+		(coord.0 < rows && coord.1 < cols).then_some(coord)
+	}
+	pub fn contains(&self, pos: Vec2) -> bool {
+		self.coord(pos).is_some()
+	}
+	pub fn get(&self, pos: Vec2) -> Option<&CellType> {
+		let (y, x) = self.coord(pos)?;
+		self.grid.get(y, x)
+	}
+	pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut CellType> {
+		let (y, x) = self.coord(pos)?;
+		self.grid.get_mut(y, x)
+	}
+	/// One step from `coord` towards `dir`, if that's still on the grid
+	pub fn step(&self, coord: GridCoord2, dir: TravelDirection) -> Option<GridCoord2> {
+		self.step_by(coord, dir, 1)
+	}
+	/// `distance` steps from `coord` towards `dir`, if that's still on the grid
+	pub fn step_by(
+		&self,
+		coord: GridCoord2,
+		dir: TravelDirection,
+		distance: usize,
+	) -> Option<GridCoord2> {
+		self.coord(Vec2::from(coord) + dir.delta() * distance as i64)
+	}
+	/// The neighbours one step away in each of `dirs` that are on the grid,
+	/// along with the direction taken to reach them
+	pub fn directed_neighbours<'a>(
+		&'a self,
+		coord: GridCoord2,
+		dirs: impl IntoIterator<Item = TravelDirection> + 'a,
+	) -> impl Iterator<Item = (TravelDirection, GridCoord2, &'a CellType)> + 'a {
+		dirs.into_iter().filter_map(move |dir| {
+			let next = self.step(coord, dir)?;
+			Some((dir, next, &self.grid[next]))
+		})
+	}
+	pub fn neighbours_in<'a>(
+		&'a self,
+		coord: GridCoord2,
+		dirs: impl IntoIterator<Item = TravelDirection> + 'a,
+	) -> impl Iterator<Item = (GridCoord2, &'a CellType)> + 'a {
+		self.directed_neighbours(coord, dirs)
+			.map(|(_, next, val)| (next, val))
+	}
+	/// The orthogonal neighbours, clockwise from N
+	pub fn neighbours4(
+		&self,
+		coord: GridCoord2,
+	) -> impl Iterator<Item = (GridCoord2, &CellType)> + '_ {
+		self.neighbours_in(coord, TravelDirection::cardinal())
+	}
+	/// The orthogonal & diagonal neighbours, clockwise from N
+	pub fn neighbours8(
+		&self,
+		coord: GridCoord2,
+	) -> impl Iterator<Item = (GridCoord2, &CellType)> + '_ {
+		self.neighbours_in(coord, TravelDirection::all())
+	}
+	/// The square of cells exactly `radius` king's moves from `coord`, in reading order.
+	/// A radius of 0 is just `coord` itself.
+	pub fn ring(
+		&self,
+		coord: GridCoord2,
+		radius: usize,
+	) -> impl Iterator<Item = (GridCoord2, &CellType)> + '_ {
+		let r = radius as i64;
+		let centre = Vec2::from(coord);
+		(-r..=r)
+			.flat_map(move |y| (-r..=r).map(move |x| Vec2::new(y, x)))
+			.filter(move |offset| offset.chebyshev() == radius as u64)
+			.filter_map(move |offset| {
+				let next = self.coord(centre + offset)?;
+				Some((next, &self.grid[next]))
+			})
+	}
 	pub fn distance_generic<T>(a: &GridCoord2, b: &GridCoord2) -> GridDistance2<T>
 	where
 		T: From<usize> + std::ops::Sub<Output = T>,
@@ -216,21 +704,257 @@ where
 		return GridDistance2(b0 - a0, b1 - a1);
 	}
 
-	pub fn travel<T>(&self, coord: GridCoord2, distance: GridDistance2<T>) -> Option<GridCoord2>
-	where
-		T: From<usize> + Into<usize> + std::ops::Add<Output = T> + std::cmp::PartialOrd<T>,
-	{
-		let coord0: T = coord.0.into();
-		let coord1: T = coord.1.into();
-		let (row, col) = (coord0 + distance.0, coord1 + distance.1);
-		if row < 0.into()
-			|| col < 0.into()
-			|| row >= self.grid.rows().into()
-			|| col >= self.grid.cols().into()
-		{
-			return None;
+	pub fn travel(&self, coord: GridCoord2, distance: impl Into<Vec2>) -> Option<GridCoord2> {
+		self.coord(Vec2::from(coord) + distance.into())
+	}
+}
+
+#[test]
+fn grid_parse_and_display_round_trip() {
+	#[derive(Debug, Default, PartialEq)]
+	enum Cell {
+		#[default]
+		Open,
+		Wall,
+	}
+	impl GridCell for Cell {
+		fn from_char(c: char) -> Option<Self> {
+			match c {
+				'.' => Some(Cell::Open),
+				'#' => Some(Cell::Wall),
+				_ => None,
+			}
+		}
+		fn to_char(&self) -> char {
+			match self {
+				Cell::Open => '.',
+				Cell::Wall => '#',
+			}
+		}
+	}
+
+	let grid = FBGrid::<Cell>::parse("\n.#.\n##.\n").unwrap();
+	assert_eq!(grid.to_string(), ".#.\n##.\n");
+	assert_eq!(grid.find_iter(Cell::Wall).count(), 3);
+
+	let err = FBGrid::<Cell>::parse(".#.\n#x.").err().unwrap();
+	assert_eq!((err.line, err.column), (2, 2));
+	let err = FBGrid::<Cell>::parse(".#.\n#.").err().unwrap();
+	assert_eq!((err.line, err.column), (2, 3));
+}
+
+#[test]
+fn derived_grid_cells_parse_and_render() {
+	#[derive(Debug, Default, PartialEq, macros::GridCell)]
+	enum Cell {
+		#[default]
+		#[cell('.')]
+		Open,
+		#[cell('^' => TravelDirection::N, 'v' => TravelDirection::S)]
+		Facing(TravelDirection),
+		#[cell(matching = char::is_ascii_digit)]
+		Digit(char),
+		#[cell(render = seen_glyph)]
+		Seen { times: u8 },
+	}
+	fn seen_glyph(cell: &Cell) -> char {
+		match cell {
+			Cell::Seen { times } if *times > 1 => '+',
+			_ => '|',
 		}
+	}
+
+	let mut grid = FBGrid::<Cell>::parse("^.7\nv0.").unwrap();
+	assert_eq!(grid.grid[(0, 0)], Cell::Facing(TravelDirection::N));
+	assert_eq!(grid.grid[(1, 1)], Cell::Digit('0'));
+	grid.grid[(0, 1)] = Cell::Seen { times: 2 };
+	assert_eq!(grid.to_string(), "^+7\nv0.\n");
+	assert_eq!(Cell::from_char('x'), None);
+	assert_eq!(Cell::from_char('|'), None);
+
+	#[derive(Debug, Default, PartialEq, macros::GridCell)]
+	#[grid_cell(crate = crate)]
+	enum Tile {
+		#[default]
+		#[cell('.')]
+		Floor,
+		#[cell('#')]
+		Wall,
+	}
+	assert_eq!(Tile::from_char('#'), Some(Tile::Wall));
+	assert_eq!(Tile::default().to_char(), '.');
+}
+
+#[test]
+fn vec2_arithmetic_and_grid_bounds() {
+	let a = Vec2::new(1, 2);
+	let b = Vec2::from((4, 0));
+	assert_eq!(a + b, Vec2::new(5, 2));
+	assert_eq!(a - b, Vec2::new(-3, 2));
+	assert_eq!(-a * 3, Vec2::new(-3, -6));
+	assert_eq!(GridCoord2::try_from(b), Ok((4, 0)));
+	assert!(GridCoord2::try_from(a - b).is_err());
+
+	let grid = FBGrid::from(Grid::from_vec(vec![1, 2, 3, 4, 5, 6], 3));
+	assert_eq!(grid.coord(Vec2::new(1, 2)), Some((1, 2)));
+	assert_eq!(grid.get(Vec2::new(1, 2)), Some(&6));
+	assert!(!grid.contains(Vec2::new(2, 0)));
+	assert!(!grid.contains(Vec2::new(0, 3)));
+	assert!(!grid.contains(Vec2::new(-1, 0)));
+}
+
+#[test]
+fn grid_distance_normalizes_by_gcd() {
+	let cases = [
+		((4, 6), (2, 3)),
+		((-4, 6), (-2, 3)),
+		((6, -4), (3, -2)),
+		((-3, -3), (-1, -1)),
+		((0, 5), (0, 1)),
+		((-7, 0), (-1, 0)),
+		((3, 5), (3, 5)),
+		((0, 0), (0, 0)),
+	];
+	for ((y, x), (ny, nx)) in cases {
+		let normal = GridDistance2(y, x).normalize();
+		assert_eq!((normal.0, normal.1), (ny, nx), "normalizing ({}, {})", y, x);
+	}
 
-		return Some((row.into(), col.into()));
+	let dist = GridDistance2(-3, 4);
+	assert_eq!(dist.manhattan(), 7);
+	assert_eq!(dist.chebyshev(), 4);
+	assert_eq!(dist.euclidean(), 5.0);
+}
+
+#[test]
+fn travel_direction_turns_and_round_trips() {
+	assert_eq!(
+		TravelDirection::all(),
+		TravelDirection::iter().collect::<Vec<_>>()
+	);
+	for dir in TravelDirection::all() {
+		assert_eq!(dir.rt90().lt90(), dir);
+		assert_eq!(dir.rt45().lt45(), dir);
+		assert_eq!(dir.rt45().rt45(), dir.rt90());
+		assert_eq!(dir.rt90().rt90(), dir.opposite());
+		assert_eq!(dir.opposite().opposite(), dir);
+		assert_eq!(dir.delta() + dir.opposite().delta(), Vec2::default());
+		assert_eq!(dir.delta().chebyshev(), 1);
+
+		let next = dir.next_coord((5, 5)).unwrap();
+		assert_eq!(Vec2::from((5, 5)) + dir.delta(), Vec2::from(next));
+
+		assert_eq!(TravelDirection::from_arrow(dir.arrow()), Some(dir));
+		assert_eq!(dir.to_string().parse(), Ok(dir));
+	}
+	for dir in TravelDirection::cardinal() {
+		let letter = dir.to_string().chars().next().unwrap();
+		assert_eq!(TravelDirection::from_compass(letter), Some(dir));
 	}
+	let udlr = "URDL"
+		.chars()
+		.map(TravelDirection::from_udlr)
+		.collect::<Vec<_>>();
+	assert_eq!(
+		udlr,
+		TravelDirection::cardinal()
+			.into_iter()
+			.map(Some)
+			.collect::<Vec<_>>()
+	);
+	assert_eq!(TravelDirection::from_arrow('v'), Some(TravelDirection::S));
+	assert_eq!(TravelDirection::from_arrow('<'), Some(TravelDirection::W));
+	assert_eq!(TravelDirection::from_compass('X'), None);
+}
+
+#[test]
+fn direction_set_operations_and_glyphs() {
+	use TravelDirection::*;
+	let mut set = DirectionSet::default();
+	assert!(set.is_empty());
+	assert!(set.insert(E));
+	assert!(set.insert(N));
+	assert!(!set.insert(E));
+	assert_eq!(set.iter().collect::<Vec<_>>(), vec![N, E]);
+	assert_eq!(set.len(), 2);
+	assert_eq!(set.glyph(), '└');
+
+	let all = TravelDirection::all().into_iter().collect::<DirectionSet>();
+	assert_eq!(all.len(), 8);
+	assert_eq!(
+		set.union(DirectionSet::from(SW)).intersection(all),
+		[N, E, SW].into_iter().collect()
+	);
+	assert!(set.remove(N));
+	assert!(!set.remove(N));
+	assert_eq!(set.glyph(), '→');
+
+	let glyphs = [
+		(vec![N, S], '↕'),
+		(vec![E, W], '↔'),
+		(TravelDirection::cardinal(), '┼'),
+		(vec![NW], '↖'),
+		(vec![NE, SW], '⤢'),
+		(vec![N, NE], '✱'),
+		(vec![], '.'),
+	];
+	for (dirs, glyph) in glyphs {
+		assert_eq!(dirs.into_iter().collect::<DirectionSet>().glyph(), glyph);
+	}
+}
+
+#[test]
+fn neighbourhoods_stay_on_the_grid() {
+	let grid = FBGrid::from(Grid::from_vec((0..25).collect(), 5));
+	let values =
+		|cells: Vec<(GridCoord2, &i32)>| cells.into_iter().map(|(_, v)| *v).collect::<Vec<_>>();
+
+	assert_eq!(values(grid.neighbours4((0, 0)).collect()), vec![1, 5]);
+	assert_eq!(
+		values(grid.neighbours4((2, 2)).collect()),
+		vec![7, 13, 17, 11]
+	);
+	assert_eq!(values(grid.neighbours8((0, 4)).collect()), vec![9, 8, 3]);
+	assert_eq!(grid.neighbours8((2, 2)).count(), 8);
+
+	let directed = grid
+		.directed_neighbours((4, 4), [TravelDirection::NW, TravelDirection::SE])
+		.collect::<Vec<_>>();
+	assert_eq!(directed, vec![(TravelDirection::NW, (3, 3), &18)]);
+
+	assert_eq!(values(grid.ring((2, 2), 0).collect()), vec![12]);
+	assert_eq!(values(grid.ring((0, 0), 1).collect()), vec![1, 5, 6]);
+	assert_eq!(grid.ring((2, 2), 2).count(), 16);
+	assert_eq!(grid.ring((0, 0), 5).count(), 0);
+}
+
+#[test]
+fn wrapping_grids_agree_with_bounded_ones_inside() {
+	let bounded = FBGrid::from(Grid::from_vec((0..30).collect::<Vec<u8>>(), 6));
+	let wrapping = bounded.clone().with_edges(Edges::Wrapping);
+	for y in 1..4 {
+		for x in 1..5 {
+			let coord = (y, x);
+			assert_eq!(
+				bounded.neighbours8(coord).collect::<Vec<_>>(),
+				wrapping.neighbours8(coord).collect::<Vec<_>>()
+			);
+			for dir in TravelDirection::all() {
+				assert_eq!(bounded.step(coord, dir), wrapping.step(coord, dir));
+				assert_eq!(bounded.step(coord, dir), dir.next_coord(coord));
+			}
+		}
+	}
+
+	assert_eq!(bounded.step((0, 0), TravelDirection::NW), None);
+	assert_eq!(wrapping.step((0, 0), TravelDirection::NW), Some((4, 5)));
+	assert_eq!(
+		wrapping.step_by((4, 5), TravelDirection::SE, 7),
+		Some((1, 0))
+	);
+	assert_eq!(wrapping.travel((2, 3), Vec2::new(-13, 9)), Some((4, 0)));
+	assert_eq!(bounded.travel((2, 3), Vec2::new(-13, 9)), None);
+	assert_eq!(wrapping.get(Vec2::new(-1, -1)), Some(&29));
+	assert_eq!(bounded.neighbours4((0, 0)).count(), 2);
+	assert_eq!(wrapping.neighbours4((0, 0)).count(), 4);
 }
@@ -0,0 +1,147 @@
+//! Walking straight lines across a grid: rays that run until they leave it, and
+//! Bresenham lines joining any two cells
+
+use crate::{FBGrid, GridCoord2, TravelDirection, Vec2};
+
+/// The positions on the Bresenham line from `from` to `to`, both ends included. Each step
+/// moves to one of the eight neighbours.
+pub fn bresenham(from: Vec2, to: Vec2) -> impl Iterator<Item = Vec2> {
+	let (step_y, step_x) = ((to.y - from.y).signum(), (to.x - from.x).signum());
+	let (dist_y, dist_x) = (-(to.y - from.y).abs(), (to.x - from.x).abs());
+	let mut error = dist_x + dist_y;
+	let mut next = Some(from);
+	std::iter::from_fn(move || {
+		let pos = next?;
+		next = (pos != to).then(|| {
+			let mut moved = pos;
+			let doubled = 2 * error;
+			if doubled >= dist_y {
+				error += dist_y;
+				moved.x += step_x;
+			}
+			if doubled <= dist_x {
+				error += dist_x;
+				moved.y += step_y;
+			}
+			moved
+		});
+		Some(pos)
+	})
+}
+
+impl<T> FBGrid<T>
+where
+	T: Default + PartialEq,
+{
+	/// The cells from `start` towards `dir` until the edge, `start` included
+	pub fn ray(
+		&self,
+		start: GridCoord2,
+		dir: TravelDirection,
+	) -> impl Iterator<Item = (GridCoord2, &T)> + '_ {
+		self.ray_by(start, dir.delta())
+	}
+	/// The cells reached from `start` by repeating `step` until the edge, `start` included.
+	/// On a `Wrapping` grid the ray stops before coming back round to `start`.
+	pub fn ray_by(
+		&self,
+		start: GridCoord2,
+		step: impl Into<Vec2>,
+	) -> impl Iterator<Item = (GridCoord2, &T)> + '_ {
+		let step = step.into();
+		let first = self.coord(Vec2::from(start));
+		std::iter::successors(first, move |coord| {
+			self.travel(*coord, step)
+				.filter(|next| Some(*next) != first)
+		})
+		.map(|coord| (coord, &self.grid[coord]))
+	}
+	/// The cells on the Bresenham line from `from` to `to`, skipping any off the grid. On a
+	/// `Wrapping` grid those points wrap round onto the opposite edge instead.
+	pub fn line(
+		&self,
+		from: GridCoord2,
+		to: GridCoord2,
+	) -> impl Iterator<Item = (GridCoord2, &T)> + '_ {
+		bresenham(Vec2::from(from), Vec2::from(to)).filter_map(|pos| {
+			let coord = self.coord(pos)?;
+			Some((coord, &self.grid[coord]))
+		})
+	}
+}
+
+#[cfg(test)]
+fn numbered(rows: usize, cols: usize) -> FBGrid<usize> {
+	FBGrid::from(grid::Grid::from_vec((0..rows * cols).collect(), cols))
+}
+
+#[test]
+fn rays_run_to_the_edge() {
+	let grid = numbered(3, 5);
+	let cells =
+		|ray: Vec<(GridCoord2, &usize)>| ray.into_iter().map(|(_, val)| *val).collect::<Vec<_>>();
+	assert_eq!(
+		cells(grid.ray((1, 1), TravelDirection::E).collect()),
+		vec![6, 7, 8, 9]
+	);
+	assert_eq!(
+		cells(grid.ray((1, 1), TravelDirection::NW).collect()),
+		vec![6, 0]
+	);
+	assert_eq!(
+		cells(grid.ray_by((0, 0), crate::GridDistance2(1, 2)).collect()),
+		vec![0, 7, 14]
+	);
+	assert_eq!(grid.ray_by((2, 2), Vec2::default()).count(), 1);
+	assert_eq!(grid.ray((3, 0), TravelDirection::N).count(), 0);
+
+	let wrapping = grid.with_edges(crate::Edges::Wrapping);
+	assert_eq!(
+		cells(wrapping.ray((1, 3), TravelDirection::E).collect()),
+		vec![8, 9, 5, 6, 7]
+	);
+}
+
+#[test]
+fn bresenham_lines_join_their_ends() {
+	let line = bresenham(Vec2::new(0, 0), Vec2::new(1, 3)).collect::<Vec<_>>();
+	assert_eq!(
+		line,
+		vec![
+			Vec2::new(0, 0),
+			Vec2::new(0, 1),
+			Vec2::new(1, 2),
+			Vec2::new(1, 3)
+		]
+	);
+
+	for to in [
+		Vec2::new(-4, 7),
+		Vec2::new(5, -2),
+		Vec2::new(-3, -3),
+		Vec2::new(0, -6),
+	] {
+		let line = bresenham(Vec2::new(1, 1), to).collect::<Vec<_>>();
+		assert_eq!(line.len() as u64, (to - Vec2::new(1, 1)).chebyshev() + 1);
+		assert_eq!(line.last(), Some(&to));
+		assert!(line
+			.windows(2)
+			.all(|pair| (pair[1] - pair[0]).chebyshev() == 1));
+	}
+
+	let grid = numbered(3, 5);
+	let coords = grid
+		.line((2, 0), (0, 4))
+		.map(|(coord, _)| coord)
+		.collect::<Vec<_>>();
+	assert_eq!(coords, vec![(2, 0), (1, 1), (1, 2), (0, 3), (0, 4)]);
+
+	let coords = |grid: &FBGrid<usize>| {
+		grid.line((0, 3), (1, 6))
+			.map(|(coord, _)| coord)
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(coords(&grid), vec![(0, 3), (0, 4)]);
+	let wrapping = grid.with_edges(crate::Edges::Wrapping);
+	assert_eq!(coords(&wrapping), vec![(0, 3), (0, 4), (1, 0), (1, 1)]);
+}
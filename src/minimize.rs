@@ -0,0 +1,263 @@
+//! Shrinks an input two implementations disagree on down to a reproducer small enough to
+//! read, by delta debugging: drop a chunk, keep the smaller input if it still fails, repeat.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::crosscheck::{compare, first_disagreement};
+use crate::input::Validation;
+use crate::{Puzzle, RootOpt};
+
+/// Drops ever smaller chunks of `units` for as long as `fails` keeps holding
+fn ddmin<T: Clone>(mut units: Vec<T>, fails: &mut impl FnMut(&[T]) -> bool) -> Vec<T> {
+	let mut chunks = 2;
+	while units.len() >= 2 {
+		let chunk_len = units.len().div_ceil(chunks);
+		let reduced = (0..units.len()).step_by(chunk_len).find_map(|start| {
+			let end = (start + chunk_len).min(units.len());
+			let candidate = [&units[..start], &units[end..]].concat();
+			fails(&candidate).then_some(candidate)
+		});
+		match reduced {
+			Some(candidate) => {
+				units = candidate;
+				chunks = (chunks - 1).max(2);
+			}
+			None if chunk_len == 1 => break,
+			None => chunks = (chunks * 2).min(units.len()),
+		}
+	}
+	units
+}
+
+/// Removes whole lines while `fails` holds
+pub fn minimize_lines(input: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+	let lines = input.lines().collect::<Vec<_>>();
+	ddmin(lines, &mut |lines| fails(&lines.join("\n"))).join("\n")
+}
+
+/// Removes rows, then columns, then replaces single cells with `blank`, while `fails` holds.
+/// Every candidate stays rectangular.
+pub fn minimize_grid(input: &str, blank: char, mut fails: impl FnMut(&str) -> bool) -> String {
+	let render = |cells: &[Vec<char>]| {
+		cells
+			.iter()
+			.map(|row| row.iter().collect::<String>())
+			.collect::<Vec<_>>()
+			.join("\n")
+	};
+	let mut cells = input
+		.lines()
+		.map(|line| line.chars().collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	loop {
+		let before = render(&cells);
+
+		cells = ddmin(cells, &mut |rows| fails(&render(rows)));
+
+		let width = cells.first().map_or(0, Vec::len);
+		let columns = ddmin((0..width).collect(), &mut |columns| {
+			fails(&render(&select_columns(&cells, columns)))
+		});
+		cells = select_columns(&cells, &columns);
+
+		let filled = cells
+			.iter()
+			.enumerate()
+			.flat_map(|(y, row)| row.iter().enumerate().map(move |(x, c)| (y, x, *c)))
+			.filter(|(_, _, c)| *c != blank)
+			.map(|(y, x, _)| (y, x))
+			.collect::<Vec<_>>();
+		let kept = ddmin(filled, &mut |kept| {
+			fails(&render(&blank_except(&cells, kept, blank)))
+		});
+		cells = blank_except(&cells, &kept, blank);
+
+		if render(&cells) == before {
+			return before;
+		}
+	}
+}
+
+fn select_columns(cells: &[Vec<char>], columns: &[usize]) -> Vec<Vec<char>> {
+	cells
+		.iter()
+		.map(|row| columns.iter().map(|x| row[*x]).collect())
+		.collect()
+}
+
+fn blank_except(cells: &[Vec<char>], kept: &[(usize, usize)], blank: char) -> Vec<Vec<char>> {
+	let kept = kept.iter().collect::<HashSet<_>>();
+	let mut result = cells.to_vec();
+	for (y, row) in result.iter_mut().enumerate() {
+		for (x, c) in row.iter_mut().enumerate() {
+			if !kept.contains(&(y, x)) {
+				*c = blank;
+			}
+		}
+	}
+	result
+}
+
+/// What every grid day parses as an empty cell, so blanking one never adds to the puzzle
+const GRID_BLANK: char = '.';
+
+/// Shrinks `input` while `part` of the puzzle still disagrees with its reference in the same
+/// way: each side must keep answering, erroring or panicking as it did on `input`. Otherwise
+/// cutting the input down to something that's no longer a valid puzzle would count, and we'd
+/// chase whichever side trips over it first. Grid days (those validating `RectangularGrid`)
+/// shrink by rows, columns & cells, blanking cells with `GRID_BLANK`.
+pub fn minimize(puzzle: &dyn Puzzle, part: u8, input: &str) -> String {
+	let Ok(Some(original)) = compare(puzzle, part, input) else {
+		return input.to_string();
+	};
+	let fails = |candidate: &str| match compare(puzzle, part, candidate) {
+		Ok(Some(disagreement)) => disagreement.endings() == original.endings(),
+		_ => false,
+	};
+	if !puzzle.validations().contains(&Validation::RectangularGrid) {
+		return minimize_lines(input, fails);
+	}
+	minimize_grid(input, GRID_BLANK, fails)
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct MinimizeCommand {
+	/// Input to shrink. Without one, crosscheck generated inputs until one disagrees.
+	#[arg(short, long)]
+	pub input: Option<PathBuf>,
+
+	/// Day-specific size of each generated input
+	#[arg(short, long, default_value_t = 20)]
+	pub size: usize,
+
+	/// Seed for the first generated input
+	#[arg(long, default_value_t = 0)]
+	pub seed: u64,
+
+	/// How many generated inputs to try before giving up
+	#[arg(short, long, default_value_t = 100)]
+	pub count: u64,
+
+	/// Folder to write the reproducer to
+	#[arg(short, long, default_value = "./input/minimized")]
+	pub out: PathBuf,
+}
+
+impl MinimizeCommand {
+	pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
+		log::info!("Running minimize command");
+		let day = crate::puzzle::solution(opt)?;
+
+		let input = match &self.input {
+			Some(path) => std::fs::read_to_string(path)?,
+			None => match first_disagreement(&*day, opt.part, self.size, self.seed, self.count)? {
+				Ok(checked) => {
+					println!("Solution & reference agree on {} inputs", checked);
+					return Ok(());
+				}
+				Err(disagreement) => disagreement.input,
+			},
+		};
+		if compare(&*day, opt.part, &input)?.is_none() {
+			anyhow::bail!("Solution & reference already agree on this input");
+		}
+
+		// Plenty of candidates make one side or the other panic; don't print every one
+		let default_hook = std::panic::take_hook();
+		std::panic::set_hook(Box::new(|_| {}));
+		let minimized = minimize(&*day, opt.part, &input);
+		std::panic::set_hook(default_hook);
+
+		let Some(disagreement) = compare(&*day, opt.part, &minimized)? else {
+			anyhow::bail!(
+				"The minimized input no longer disagrees, is the solution deterministic?"
+			);
+		};
+		println!("{}", disagreement);
+
+		std::fs::create_dir_all(&self.out)?;
+		let name = format!("day{:02}_part{}", opt.day, opt.part);
+		let input_path = self.out.join(format!("{}.txt", name));
+		std::fs::write(&input_path, &minimized)?;
+		// Ready to paste into the day's `examples()`, with the reference's answer as expected
+		let example = format!(
+			"Example::new(\n\t\"minimized\",\n\tr#\"\n{}\n\"#,\n)\n.part_{}({:?})\n",
+			minimized,
+			match opt.part {
+				1 => "one",
+				_ => "two",
+			},
+			disagreement.reference
+		);
+		let example_path = self.out.join(format!("{}.rs", name));
+		std::fs::write(&example_path, example)?;
+		println!(
+			"Wrote {} and {}",
+			input_path.display(),
+			example_path.display()
+		);
+		Ok(())
+	}
+}
+
+#[test]
+fn minimize_lines_keeps_only_the_culprits() {
+	let input = (1..=40)
+		.map(|n| n.to_string())
+		.collect::<Vec<_>>()
+		.join("\n");
+	let fails = |text: &str| text.lines().any(|l| l == "7") && text.lines().any(|l| l == "31");
+	assert_eq!(minimize_lines(&input, fails), "7\n31");
+}
+
+#[test]
+fn minimize_grid_stays_rectangular() {
+	let input = "..#..\n.....\n#.a..\n....#\n.b...";
+	let fails = |text: &str| text.contains('a') && text.contains('b');
+	assert_eq!(minimize_grid(input, '.', fails), ".a\nb.");
+}
+
+#[test]
+fn minimize_keeps_the_original_kind_of_disagreement() {
+	struct Doubler;
+	impl Puzzle for Doubler {
+		fn new(_ops: &RootOpt) -> Box<dyn Puzzle> {
+			Box::new(Self)
+		}
+		// Double counts the bugs, but needs its rules to run at all
+		fn part_one(&self, input: &str) -> crate::puzzle::PuzzleResult {
+			assert!(input.contains("rules"), "no rules");
+			Ok((input.matches("bug").count() * 2).to_string())
+		}
+		fn part_two(&self, _input: &str) -> crate::puzzle::PuzzleResult {
+			Ok(String::new())
+		}
+		fn reference(&self, _part: u8) -> Option<crate::crosscheck::ReferenceFn> {
+			Some(|input| Ok(input.matches("bug").count().to_string()))
+		}
+	}
+
+	let default_hook = std::panic::take_hook();
+	std::panic::set_hook(Box::new(|_| {}));
+	let minimized = minimize(&Doubler, 1, "a\nrules\nb\nbug\nc");
+	std::panic::set_hook(default_hook);
+	assert_eq!(minimized, "rules\nbug");
+}
+
+#[test]
+fn grid_days_allow_the_blank() {
+	for day in macros::solution_days!() {
+		let validations = crate::puzzle::solution_for(day).validations();
+		if !validations.contains(&Validation::RectangularGrid) {
+			continue;
+		}
+		let allowed = validations.iter().all(|validation| match validation {
+			Validation::AllowedChars(allowed) => allowed.contains(GRID_BLANK),
+			_ => true,
+		});
+		assert!(allowed, "day {:02} can't have blanked cells", day);
+	}
+}
@@ -0,0 +1,467 @@
+//! Shortest paths over any state space, plus `FBGrid` shorthands for the usual grid walks.
+//! States are anything hashable: a `GridCoord2`, or a `Heading` when turning costs extra.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use crate::{Edges, FBGrid, GridCoord2, TravelDirection, Vec2};
+
+/// Where a walker stands & which way it faces
+pub type Heading = (GridCoord2, TravelDirection);
+
+/// A route from the start to a goal, both included
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path<S> {
+	pub states: Vec<S>,
+	pub cost: u64,
+}
+
+/// Every state reached so far, by index, with the cheapest known cost & the states that
+/// reach it at that cost
+struct Explored<S> {
+	states: Vec<S>,
+	index: HashMap<S, usize>,
+	cost: Vec<u64>,
+	parents: Vec<Vec<usize>>,
+}
+impl<S: Clone + Eq + Hash> Explored<S> {
+	fn new(start: S) -> Self {
+		Self {
+			states: vec![start.clone()],
+			index: HashMap::from([(start, 0)]),
+			cost: vec![0],
+			parents: vec![Vec::new()],
+		}
+	}
+	fn id(&mut self, state: S) -> usize {
+		if let Some(idx) = self.index.get(&state) {
+			return *idx;
+		}
+		self.states.push(state.clone());
+		self.cost.push(u64::MAX);
+		self.parents.push(Vec::new());
+		self.index.insert(state, self.states.len() - 1);
+		self.states.len() - 1
+	}
+	/// Follows the first parent of each state back to the start
+	fn path_to(&self, goal: usize) -> Path<S> {
+		let mut idxs = vec![goal];
+		while let Some(parent) = self.parents[*idxs.last().unwrap()].first() {
+			idxs.push(*parent);
+		}
+		Path {
+			states: idxs
+				.iter()
+				.rev()
+				.map(|idx| self.states[*idx].clone())
+				.collect(),
+			cost: self.cost[goal],
+		}
+	}
+}
+
+/// Fewest steps from `start` to a state where `is_goal` holds
+pub fn bfs<S, I>(
+	start: S,
+	mut successors: impl FnMut(&S) -> I,
+	mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S>>
+where
+	S: Clone + Eq + Hash,
+	I: IntoIterator<Item = S>,
+{
+	let mut explored = Explored::new(start);
+	let mut queue = VecDeque::from([0]);
+	while let Some(idx) = queue.pop_front() {
+		if is_goal(&explored.states[idx]) {
+			return Some(explored.path_to(idx));
+		}
+		let state = explored.states[idx].clone();
+		for next in successors(&state) {
+			let next_idx = explored.id(next);
+			if explored.cost[next_idx] == u64::MAX {
+				explored.cost[next_idx] = explored.cost[idx] + 1;
+				explored.parents[next_idx].push(idx);
+				queue.push_back(next_idx);
+			}
+		}
+	}
+	None
+}
+
+/// Cheapest route from `start` to a goal, where `successors` gives each next state along
+/// with the cost of stepping to it
+pub fn dijkstra<S, I>(
+	start: S,
+	successors: impl FnMut(&S) -> I,
+	is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S>>
+where
+	S: Clone + Eq + Hash,
+	I: IntoIterator<Item = (S, u64)>,
+{
+	astar(start, successors, |_| 0, is_goal)
+}
+
+/// Dijkstra, but exploring states that `heuristic` estimates are closer to a goal first.
+/// The estimate must never exceed the real remaining cost, or the path found may not be
+/// the cheapest.
+pub fn astar<S, I>(
+	start: S,
+	successors: impl FnMut(&S) -> I,
+	heuristic: impl FnMut(&S) -> u64,
+	is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S>>
+where
+	S: Clone + Eq + Hash,
+	I: IntoIterator<Item = (S, u64)>,
+{
+	let (explored, goals) = search(start, successors, heuristic, is_goal, false)?;
+	Some(explored.path_to(goals[0]))
+}
+
+/// Like `dijkstra`, but keeps every route that ties for cheapest, to any goal state. A tie
+/// is only recorded into a state that hasn't been expanded yet, so parents are always
+/// settled before their children and a free step can't loop a route back on itself.
+pub fn all_shortest_paths<S, I>(
+	start: S,
+	successors: impl FnMut(&S) -> I,
+	is_goal: impl FnMut(&S) -> bool,
+) -> Option<ShortestPaths<S>>
+where
+	S: Clone + Eq + Hash,
+	I: IntoIterator<Item = (S, u64)>,
+{
+	let (explored, goals) = search(start, successors, |_| 0, is_goal, true)?;
+	Some(ShortestPaths {
+		cost: explored.cost[goals[0]],
+		explored,
+		goals,
+	})
+}
+
+/// Best-first search, returning the goals reached at the lowest cost. Without `keep_ties`
+/// it stops at the first goal & keeps one parent per state.
+fn search<S, I>(
+	start: S,
+	mut successors: impl FnMut(&S) -> I,
+	mut heuristic: impl FnMut(&S) -> u64,
+	mut is_goal: impl FnMut(&S) -> bool,
+	keep_ties: bool,
+) -> Option<(Explored<S>, Vec<usize>)>
+where
+	S: Clone + Eq + Hash,
+	I: IntoIterator<Item = (S, u64)>,
+{
+	let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+	let mut explored = Explored::new(start);
+	let mut expanded = HashSet::new();
+	let mut goals = Vec::new();
+	while let Some(Reverse((_, cost, idx))) = queue.pop() {
+		if cost > explored.cost[idx] || !expanded.insert(idx) {
+			// Already reached more cheaply, or already expanded at this cost
+			continue;
+		}
+		if goals
+			.first()
+			.is_some_and(|goal: &usize| cost > explored.cost[*goal])
+		{
+			break;
+		}
+		if is_goal(&explored.states[idx]) {
+			goals.push(idx);
+			if !keep_ties {
+				break;
+			}
+			continue;
+		}
+		let state = explored.states[idx].clone();
+		for (next, step) in successors(&state) {
+			let next_cost = cost + step;
+			let next_idx = explored.id(next);
+			match next_cost.cmp(&explored.cost[next_idx]) {
+				Ordering::Less => {
+					explored.cost[next_idx] = next_cost;
+					explored.parents[next_idx] = vec![idx];
+					let estimate = next_cost + heuristic(&explored.states[next_idx]);
+					queue.push(Reverse((estimate, next_cost, next_idx)));
+				}
+				// Once expanded, a state may be an ancestor of this one
+				Ordering::Equal if keep_ties && !expanded.contains(&next_idx) => {
+					explored.parents[next_idx].push(idx)
+				}
+				_ => {}
+			}
+		}
+	}
+	(!goals.is_empty()).then_some((explored, goals))
+}
+
+/// Every cheapest route to the goal, from `all_shortest_paths`
+pub struct ShortestPaths<S> {
+	pub cost: u64,
+	explored: Explored<S>,
+	goals: Vec<usize>,
+}
+impl<S: Clone + Eq + Hash> ShortestPaths<S> {
+	/// Each route in full. There can be exponentially many, so prefer `states` when only
+	/// which states are on a route matters.
+	pub fn paths(&self) -> Vec<Vec<S>> {
+		let mut paths = Vec::new();
+		let mut pending = self
+			.goals
+			.iter()
+			.map(|goal| vec![*goal])
+			.collect::<Vec<_>>();
+		while let Some(partial) = pending.pop() {
+			let parents = &self.explored.parents[*partial.last().unwrap()];
+			if parents.is_empty() {
+				paths.push(
+					partial
+						.iter()
+						.rev()
+						.map(|idx| self.explored.states[*idx].clone())
+						.collect(),
+				);
+			}
+			for parent in parents {
+				pending.push([partial.as_slice(), &[*parent]].concat());
+			}
+		}
+		paths
+	}
+	/// Every state on at least one of the routes
+	pub fn states(&self) -> HashSet<S> {
+		let mut seen = HashSet::new();
+		let mut pending = self.goals.clone();
+		while let Some(idx) = pending.pop() {
+			if seen.insert(idx) {
+				pending.extend(&self.explored.parents[idx]);
+			}
+		}
+		seen.iter()
+			.map(|idx| self.explored.states[*idx].clone())
+			.collect()
+	}
+}
+
+impl<T> FBGrid<T>
+where
+	T: Default + PartialEq,
+{
+	/// Fewest orthogonal steps from `start` to `goal`, only through cells where `passable` holds
+	pub fn shortest_path(
+		&self,
+		start: GridCoord2,
+		goal: GridCoord2,
+		passable: impl Fn(&T) -> bool,
+	) -> Option<Path<GridCoord2>> {
+		bfs(
+			start,
+			|coord| {
+				self.neighbours4(*coord)
+					.filter(|(_, val)| passable(val))
+					.map(|(next, _)| next)
+					.collect::<Vec<_>>()
+			},
+			|coord| *coord == goal,
+		)
+	}
+	/// Cheapest orthogonal route from `start` to `goal`, where `cost` is what entering a cell
+	/// costs, or `None` if it can't be entered. Every cost must be at least 1, as the search
+	/// is guided by the Manhattan distance left.
+	pub fn cheapest_path(
+		&self,
+		start: GridCoord2,
+		goal: GridCoord2,
+		cost: impl Fn(&T) -> Option<u64>,
+	) -> Option<Path<GridCoord2>> {
+		astar(
+			start,
+			|coord| {
+				self.neighbours4(*coord)
+					.filter_map(|(next, val)| Some((next, cost(val)?)))
+					.collect::<Vec<_>>()
+			},
+			|coord| match self.edges() {
+				Edges::Bounded => (Vec2::from(goal) - Vec2::from(*coord)).manhattan(),
+				// Going round the back may be shorter than the Manhattan distance
+				Edges::Wrapping => 0,
+			},
+			|coord| *coord == goal,
+		)
+	}
+	/// What a walker facing `dir` can do next: step forward onto a `passable` cell for
+	/// `step_cost`, or turn 90° either way on the spot for `turn_cost`
+	pub fn turning_moves(
+		&self,
+		(coord, dir): Heading,
+		passable: impl Fn(&T) -> bool,
+		step_cost: u64,
+		turn_cost: u64,
+	) -> Vec<(Heading, u64)> {
+		let mut moves = vec![
+			((coord, dir.rt90()), turn_cost),
+			((coord, dir.lt90()), turn_cost),
+		];
+		if let Some(next) = self
+			.step(coord, dir)
+			.filter(|next| passable(&self.grid[*next]))
+		{
+			moves.push(((next, dir), step_cost));
+		}
+		moves
+	}
+}
+
+#[cfg(test)]
+fn maze(text: &str) -> (FBGrid<bool>, GridCoord2, GridCoord2) {
+	let lines = text.trim().lines().collect::<Vec<_>>();
+	let cells = lines.concat().chars().collect::<Vec<_>>();
+	let find = |c| {
+		let idx = cells.iter().position(|cell| *cell == c).unwrap();
+		(idx / lines[0].len(), idx % lines[0].len())
+	};
+	let open = cells.iter().map(|c| *c != '#').collect();
+	let grid = FBGrid::from(grid::Grid::from_vec(open, lines[0].len()));
+	(grid, find('S'), find('E'))
+}
+
+#[test]
+fn grid_searches_find_the_shortest_route() {
+	let (grid, start, end) = maze(
+		r#"
+S.#....
+.##.##.
+...#...
+.#...#E
+"#,
+	);
+	let path = grid.shortest_path(start, end, |open| *open).unwrap();
+	assert_eq!(path.cost, 11);
+	assert_eq!(path.states.first(), Some(&start));
+	assert_eq!(path.states.last(), Some(&end));
+	for (a, b) in path.states.iter().zip(&path.states[1..]) {
+		assert_eq!((Vec2::from(*b) - Vec2::from(*a)).manhattan(), 1);
+		assert!(grid.grid[*b]);
+	}
+
+	let cheapest = grid
+		.cheapest_path(start, end, |open| open.then_some(1))
+		.unwrap();
+	assert_eq!(cheapest.cost, path.cost);
+	let dijkstra_cost = dijkstra(
+		start,
+		|coord| {
+			grid.neighbours4(*coord)
+				.filter(|(_, open)| **open)
+				.map(|(next, _)| (next, 1))
+		},
+		|coord| *coord == end,
+	)
+	.unwrap()
+	.cost;
+	assert_eq!(dijkstra_cost, path.cost);
+
+	assert_eq!(
+		grid.shortest_path(start, (0, 3), |open| *open)
+			.map(|p| p.cost),
+		Some(15)
+	);
+	assert_eq!(grid.shortest_path(start, (1, 2), |open| *open), None);
+}
+
+#[test]
+fn all_shortest_paths_with_turn_costs() {
+	let (grid, start, end) = maze(
+		r#"
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+"#,
+	);
+	let routes = all_shortest_paths(
+		(start, TravelDirection::E),
+		|heading| grid.turning_moves(*heading, |open| *open, 1, 1000),
+		|(coord, _)| *coord == end,
+	)
+	.unwrap();
+	assert_eq!(routes.cost, 7036);
+	let tiles = routes
+		.states()
+		.iter()
+		.map(|(coord, _)| *coord)
+		.collect::<HashSet<_>>();
+	assert_eq!(tiles.len(), 45);
+	assert_eq!(routes.paths().len(), 3);
+}
+
+#[test]
+fn all_shortest_paths_enumerates_ties() {
+	let (grid, start, end) = maze("S..\n...\n..E");
+	let routes = all_shortest_paths(
+		start,
+		|coord| {
+			grid.neighbours4(*coord)
+				.map(|(next, _)| (next, 1))
+				.collect::<Vec<_>>()
+		},
+		|coord| *coord == end,
+	)
+	.unwrap();
+	assert_eq!(routes.cost, 4);
+	let paths = routes.paths();
+	assert_eq!(paths.len(), 6);
+	assert!(paths.iter().all(|path| path.len() == 5 && path[0] == start));
+	assert_eq!(routes.states().len(), 9);
+}
+
+#[test]
+fn free_steps_dont_loop_tied_routes() {
+	// 0 & 1 are a free step apart, both ways
+	let routes = all_shortest_paths(
+		0,
+		|state: &u8| match state {
+			0 => vec![(1, 0)],
+			1 => vec![(0, 0), (2, 1)],
+			_ => vec![],
+		},
+		|state| *state == 2,
+	)
+	.unwrap();
+	assert_eq!(routes.cost, 1);
+	assert_eq!(routes.paths(), vec![vec![0, 1, 2]]);
+	assert_eq!(routes.states().len(), 3);
+}
+
+#[test]
+fn free_steps_keep_tied_routes() {
+	// S = 0, A = 1, B = 2, C = 3: both A & B reach C for free
+	let routes = all_shortest_paths(
+		0,
+		|state: &u8| match state {
+			0 => vec![(1, 1), (2, 1)],
+			1 | 2 => vec![(3, 0)],
+			_ => vec![],
+		},
+		|state| *state == 3,
+	)
+	.unwrap();
+	assert_eq!(routes.cost, 1);
+	let mut paths = routes.paths();
+	paths.sort();
+	assert_eq!(paths, vec![vec![0, 1, 3], vec![0, 2, 3]]);
+}
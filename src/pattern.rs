@@ -0,0 +1,159 @@
+//! Finding a small template grid inside a bigger one, e.g. the X-MAS cross
+//!
+//! ```text
+//! M.S
+//! .A.
+//! M.S
+//! ```
+//!
+//! where `.` matches anything, optionally in every orientation.
+
+use crate::input::InputError;
+use crate::transform::Orientation;
+use crate::{FBGrid, GridCell, GridCoord2};
+
+/// A template to search for, where wildcard cells match anything
+pub struct Pattern<T>
+where
+	T: Default + PartialEq,
+{
+	grid: FBGrid<Option<T>>,
+	orientations: Vec<Orientation>,
+}
+impl<T> Pattern<T>
+where
+	T: GridCell + Default + PartialEq,
+{
+	/// One cell per character like `FBGrid::parse`, with `wildcard` matching any cell
+	pub fn parse(input: &str, wildcard: char) -> Result<Self, InputError> {
+		let grid = FBGrid::parse_with(input, |c| match c == wildcard {
+			true => Some(None),
+			false => T::from_char(c).map(Some),
+		})?;
+		Ok(Self {
+			grid,
+			orientations: vec![Orientation::IDENTITY],
+		})
+	}
+}
+impl<T> Pattern<T>
+where
+	T: Default + PartialEq,
+{
+	/// Also match the pattern turned & mirrored every way
+	pub fn any_orientation(self) -> Self {
+		self.with_orientations(Orientation::all())
+	}
+	pub fn with_orientations(mut self, orientations: Vec<Orientation>) -> Self {
+		self.orientations = orientations;
+		self
+	}
+
+	/// The pattern's fixed cells in each orientation, as (offset, cell) pairs in the
+	/// unturned pattern's reading order. Orientations that look the same as an earlier
+	/// one (as symmetric patterns do) are left out, so nothing matches twice.
+	fn arrangements(&self) -> Vec<(Orientation, Vec<(GridCoord2, &T)>)> {
+		let mut arrangements: Vec<(Orientation, Vec<(GridCoord2, &T)>)> = Vec::new();
+		for orientation in self.orientations.iter() {
+			let view = self.grid.oriented(*orientation);
+			let mut fixed = view
+				.indexed_iter()
+				.filter_map(|(at, cell)| Some((view.source(at).unwrap(), at, cell.as_ref()?)))
+				.collect::<Vec<_>>();
+			let looks_the_same = |(_, other): &(Orientation, Vec<(GridCoord2, &T)>)| {
+				other.len() == fixed.len()
+					&& fixed
+						.iter()
+						.all(|(_, at, cell)| other.contains(&(*at, *cell)))
+			};
+			if arrangements.iter().any(looks_the_same) {
+				continue;
+			}
+			fixed.sort_by_key(|(source, _, _)| *source);
+			let offsets = fixed.into_iter().map(|(_, at, cell)| (at, cell)).collect();
+			arrangements.push((*orientation, offsets));
+		}
+		arrangements
+	}
+}
+
+/// Somewhere a `Pattern` matched
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternMatch {
+	pub orientation: Orientation,
+	/// Where the pattern's fixed cells matched, in the unturned pattern's reading order
+	pub cells: Vec<GridCoord2>,
+}
+
+impl<T> FBGrid<T>
+where
+	T: Default + PartialEq,
+{
+	/// Every placement of `pattern` that fits on the grid with all its fixed cells equal,
+	/// orientation by orientation and then in reading order
+	pub fn find_pattern(&self, pattern: &Pattern<T>) -> Vec<PatternMatch> {
+		let mut matches = Vec::new();
+		for (orientation, offsets) in pattern.arrangements() {
+			let view = pattern.grid.oriented(orientation);
+			let last_y = (self.grid.rows() + 1).saturating_sub(view.rows());
+			let last_x = (self.grid.cols() + 1).saturating_sub(view.cols());
+			for (y, x) in (0..last_y).flat_map(|y| (0..last_x).map(move |x| (y, x))) {
+				let found = offsets
+					.iter()
+					.all(|((dy, dx), cell)| self.grid[(y + dy, x + dx)] == **cell);
+				if found {
+					matches.push(PatternMatch {
+						orientation,
+						cells: offsets
+							.iter()
+							.map(|((dy, dx), _)| (y + dy, x + dx))
+							.collect(),
+					});
+				}
+			}
+		}
+		matches
+	}
+}
+
+#[cfg(test)]
+use crate::testing::Letter;
+
+#[test]
+fn patterns_match_in_every_orientation() {
+	let grid = FBGrid::<Letter>::parse("MAS.\nA..S\nSAMA").unwrap();
+
+	let across = Pattern::<Letter>::parse("MAS", '?').unwrap();
+	let found = grid.find_pattern(&across);
+	assert_eq!(found.len(), 1);
+	assert_eq!(found[0].cells, vec![(0, 0), (0, 1), (0, 2)]);
+
+	// Also down the first column, & backwards along the last row
+	let found = grid.find_pattern(&across.any_orientation());
+	assert_eq!(
+		found.iter().map(|m| m.cells.clone()).collect::<Vec<_>>(),
+		vec![
+			vec![(0, 0), (0, 1), (0, 2)],
+			vec![(0, 0), (1, 0), (2, 0)],
+			vec![(2, 2), (2, 1), (2, 0)],
+		]
+	);
+}
+
+#[test]
+fn wildcards_and_symmetric_patterns() {
+	let grid = FBGrid::<Letter>::parse("MXS\nXAX\nMXS\nSXS\nXAX\nMXM").unwrap();
+	let cross = Pattern::<Letter>::parse("M?S\n?A?\nM?S", '?')
+		.unwrap()
+		.any_orientation();
+	// Mirroring & turning gives just four distinct crosses, which match twice here
+	assert_eq!(cross.arrangements().len(), 4);
+	let found = grid.find_pattern(&cross);
+	assert_eq!(found.len(), 2);
+	assert_eq!(found[0].cells, vec![(0, 0), (0, 2), (1, 1), (2, 0), (2, 2)]);
+	assert_eq!(found[1].orientation, Orientation::ROTATE_LEFT);
+	// The unturned pattern's top-left M ends up bottom-left
+	assert_eq!(found[1].cells[0], (5, 0));
+
+	assert!(Pattern::<Letter>::parse("M?\n?", '?').is_err());
+}
@@ -1,11 +1,24 @@
 // Include deps that will be available in every puzzle
 
+pub mod parsing;
+pub use parsing::{int_columns, int_rows};
+
+pub use crate::crosscheck::ReferenceFn;
+pub use crate::generate::InputGenerator;
+pub use crate::input::{InputError, TrimPolicy, Validation};
+pub use crate::pattern::Pattern;
+pub use crate::puzzle::Example;
 pub use crate::Puzzle;
 pub use anyhow::Error;
 pub use itertools::Itertools;
+// The derive macro, alongside the trait of the same name
+pub use macros::GridCell;
 
 #[allow(unused_imports)]
-pub use crate::{grid_from_vec_vec, FBGrid, GridCoord2, GridDistance2, TravelDirection};
+pub use crate::{
+	grid_from_vec_vec, DirectionSet, FBGrid, GridCell, GridCoord2, GridDistance2, TravelDirection,
+	Vec2,
+};
 
 #[allow(unused_imports)]
 pub use std::str::FromStr;
@@ -0,0 +1,172 @@
+//! Line-oriented integer parsing shared by the puzzles.
+//! Blank lines are skipped, but errors still report the line/column in the original text.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use itertools::traits::HomogeneousTuple;
+use itertools::Itertools;
+
+use crate::input::InputError;
+
+/// Splits a line on whitespace, keeping the 1-based column each token starts at
+fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+	line.split_whitespace().map(move |token| {
+		let byte_offset = token.as_ptr() as usize - line.as_ptr() as usize;
+		(line[..byte_offset].chars().count() + 1, token)
+	})
+}
+
+fn parse_token<T>(line: usize, column: usize, token: &str) -> Result<T, InputError>
+where
+	T: FromStr,
+	T::Err: Display,
+{
+	token
+		.parse::<T>()
+		.map_err(|e| InputError::new(line, column, format!("couldn't parse {:?}: {}", token, e)))
+}
+
+/// Non-blank lines paired with their 1-based line number
+fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+	input
+		.lines()
+		.enumerate()
+		.map(|(idx, line)| (idx + 1, line))
+		.filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// Every non-blank line as a row of whitespace-separated integers, e.g. `7 6 4 2 1`
+pub fn int_rows<T>(input: &str) -> Result<Vec<Vec<T>>, InputError>
+where
+	T: FromStr,
+	T::Err: Display,
+{
+	numbered_lines(input)
+		.map(|(line_no, line)| {
+			tokens(line)
+				.map(|(column, token)| parse_token(line_no, column, token))
+				.collect()
+		})
+		.collect()
+}
+
+/// Whitespace-separated integers read down the page, e.g. the two lists in `3   4`.
+/// Every row must have the same number of values.
+pub fn int_columns<T>(input: &str) -> Result<Vec<Vec<T>>, InputError>
+where
+	T: FromStr,
+	T::Err: Display,
+{
+	let mut columns: Vec<Vec<T>> = Vec::new();
+	for (row_idx, (line_no, line)) in numbered_lines(input).enumerate() {
+		let row = tokens(line)
+			.map(|(column, token)| parse_token(line_no, column, token))
+			.collect::<Result<Vec<T>, _>>()?;
+		if row_idx == 0 {
+			columns = row.into_iter().map(|value| vec![value]).collect();
+			continue;
+		}
+		if row.len() != columns.len() {
+			return Err(InputError::new(
+				line_no,
+				1,
+				format!("expected {} values, found {}", columns.len(), row.len()),
+			));
+		}
+		for (column, value) in columns.iter_mut().zip(row) {
+			column.push(value);
+		}
+	}
+	Ok(columns)
+}
+
+/// Every non-blank line as a fixed-width tuple, e.g. `(i32, i32)` for `3   4`
+pub fn int_tuples<Tup>(input: &str) -> Result<Vec<Tup>, InputError>
+where
+	Tup: HomogeneousTuple,
+	Tup::Item: FromStr,
+	<Tup::Item as FromStr>::Err: Display,
+{
+	numbered_lines(input)
+		.map(|(line_no, line)| {
+			let values = tokens(line)
+				.map(|(column, token)| parse_token(line_no, column, token))
+				.collect::<Result<Vec<Tup::Item>, _>>()?;
+			let found = values.len();
+			values.into_iter().collect_tuple().ok_or_else(|| {
+				InputError::new(
+					line_no,
+					1,
+					format!("expected {} values, found {}", Tup::num_items(), found),
+				)
+			})
+		})
+		.collect()
+}
+
+/// Every integer (with an optional leading `-`) embedded anywhere in the text,
+/// e.g. `p=0,4 v=-3,3` yields `[0, 4, -3, 3]`
+pub fn signed_ints<T>(text: &str) -> Result<Vec<T>, InputError>
+where
+	T: FromStr,
+	T::Err: Display,
+{
+	let mut result = Vec::new();
+	for (line_no, line) in numbered_lines(text) {
+		let chars = line.char_indices().collect::<Vec<_>>();
+		let mut idx = 0;
+		while idx < chars.len() {
+			let (_, c) = chars[idx];
+			let negative = c == '-' && chars.get(idx + 1).is_some_and(|(_, n)| n.is_ascii_digit());
+			if !negative && !c.is_ascii_digit() {
+				idx += 1;
+				continue;
+			}
+			let start = idx;
+			idx += 1;
+			while idx < chars.len() && chars[idx].1.is_ascii_digit() {
+				idx += 1;
+			}
+			let start_byte = chars[start].0;
+			let end_byte = chars.get(idx).map_or(line.len(), |(b, _)| *b);
+			result.push(parse_token(
+				line_no,
+				start + 1,
+				&line[start_byte..end_byte],
+			)?);
+		}
+	}
+	Ok(result)
+}
+
+#[test]
+fn parses_rows_columns_and_tuples() {
+	let input = "\n3   4\n4   3\n\n2   5\n";
+	assert_eq!(
+		int_columns::<i32>(input).unwrap(),
+		vec![vec![3, 4, 2], vec![4, 3, 5]]
+	);
+	assert_eq!(
+		int_rows::<u8>("7 6 4\n1 2").unwrap(),
+		vec![vec![7, 6, 4], vec![1, 2]]
+	);
+	assert_eq!(
+		int_tuples::<(i64, i64)>(input).unwrap(),
+		vec![(3, 4), (4, 3), (2, 5)]
+	);
+	assert_eq!(
+		signed_ints::<i32>("p=0,4 v=-3,3\nx-1").unwrap(),
+		vec![0, 4, -3, 3, -1]
+	);
+}
+
+#[test]
+fn parse_errors_carry_line_numbers() {
+	let err = int_rows::<u8>("1 2\n\n3 300").unwrap_err();
+	assert_eq!((err.line, err.column), (3, 3));
+	let err = int_tuples::<(i32, i32)>("1 2\n3 4 5").unwrap_err();
+	assert_eq!((err.line, err.column), (2, 1));
+	let err = int_columns::<i32>("1 2\n3").unwrap_err();
+	assert_eq!(err.line, 2);
+}
@@ -6,8 +6,17 @@ use std::io::Read;
 use aoc_client::SubmissionOutcome;
 use clap::Parser;
 use macros::get_solution;
+#[cfg(test)]
+use macros::solution_days;
 
-use crate::{client::Client, RootOpt};
+use crate::{
+	client::Client,
+	crosscheck::ReferenceFn,
+	diagnostic::Diagnostic,
+	generate::InputGenerator,
+	input::{TrimPolicy, Validation},
+	RootOpt,
+};
 
 pub type PuzzleResult = Result<String, anyhow::Error>;
 
@@ -18,6 +27,61 @@ pub trait Puzzle {
 
 	fn part_one(&self, _input: &str) -> PuzzleResult;
 	fn part_two(&self, _input: &str) -> PuzzleResult;
+
+	/// How the runner should trim the raw input before the puzzle sees it
+	fn trim_policy(&self) -> TrimPolicy {
+		TrimPolicy::default()
+	}
+	/// Checks the runner performs on the normalized input before solving
+	fn validations(&self) -> Vec<Validation> {
+		Vec::new()
+	}
+	/// Worked examples from the assignment, checked by `cargo test`
+	fn examples(&self) -> Vec<Example> {
+		Vec::new()
+	}
+	/// Days that can invent random inputs return themselves here
+	fn generator(&self) -> Option<&dyn InputGenerator> {
+		None
+	}
+	/// A slow but obviously correct solution for `part`, for `crosscheck` to compare against
+	fn reference(&self, _part: u8) -> Option<ReferenceFn> {
+		None
+	}
+}
+
+/// A worked example from the assignment, and the answers each part should give for it
+#[derive(Debug, Clone)]
+pub struct Example {
+	pub name: &'static str,
+	pub input: &'static str,
+	pub part_one: Option<&'static str>,
+	pub part_two: Option<&'static str>,
+}
+impl Example {
+	pub fn new(name: &'static str, input: &'static str) -> Self {
+		Self {
+			name,
+			input,
+			part_one: None,
+			part_two: None,
+		}
+	}
+	pub fn part_one(mut self, answer: &'static str) -> Self {
+		self.part_one = Some(answer);
+		self
+	}
+	pub fn part_two(mut self, answer: &'static str) -> Self {
+		self.part_two = Some(answer);
+		self
+	}
+	pub fn answer(&self, part: u8) -> Option<&'static str> {
+		match part {
+			1 => self.part_one,
+			2 => self.part_two,
+			_ => None,
+		}
+	}
 }
 
 #[derive(Clone, Debug, Parser, Default)]
@@ -39,11 +103,26 @@ impl PuzzleCommand {
 		};
 
 		let day = get_solution!(opt);
+		let data = crate::input::prepare(&**day, &data)?;
 		let solution = match opt.part {
-			1 => day.part_one(&data)?,
-			2 => day.part_two(&data)?,
+			1 => day.part_one(&data),
+			2 => day.part_two(&data),
 			_ => todo!("Implement part three"),
 		};
+		let solution = match solution {
+			Ok(solution) => solution,
+			Err(err) => match err.downcast_ref::<Diagnostic>() {
+				Some(diagnostic) => {
+					eprint!("{}", diagnostic);
+					anyhow::bail!(
+						"Couldn't parse {} ({} errors)",
+						diagnostic.file_name,
+						diagnostic.error_count
+					)
+				}
+				None => return Err(err),
+			},
+		};
 
 		println!("Solution: {}", solution);
 
@@ -62,3 +141,89 @@ impl PuzzleCommand {
 		Ok(())
 	}
 }
+
+/// The puzzle for `opt.day`, for commands living outside this module
+pub(crate) fn solution(opt: &RootOpt) -> Result<Box<dyn Puzzle>, anyhow::Error> {
+	// get_solution! boxes the already-boxed puzzle
+	Ok(*get_solution!(opt))
+}
+
+#[cfg(test)]
+pub(crate) fn solution_for(day: u8) -> Box<dyn Puzzle> {
+	let opt = &RootOpt::parse_from(["advent2024", "--day", &day.to_string()]);
+	solution(opt).expect("solution_days! and get_solution! disagree")
+}
+
+/// Prepares `input` as the runner would, then solves `part` of it
+pub(crate) fn run_part(puzzle: &dyn Puzzle, part: u8, input: &str) -> PuzzleResult {
+	let input = crate::input::prepare(puzzle, input)?;
+	match part {
+		1 => puzzle.part_one(&input),
+		_ => puzzle.part_two(&input),
+	}
+}
+
+#[test]
+fn examples_give_expected_answers() {
+	let mut failures: Vec<String> = Vec::new();
+	for day in solution_days!() {
+		let puzzle = solution_for(day);
+		for example in puzzle.examples() {
+			for part in [1, 2] {
+				let Some(expected) = example.answer(part) else {
+					continue;
+				};
+				match run_part(&*puzzle, part, example.input) {
+					Ok(actual) if actual == expected => {}
+					Ok(actual) => failures.push(format!(
+						"day {:02} part {} {:?}: expected {}, got {}",
+						day, part, example.name, expected, actual
+					)),
+					Err(e) => failures.push(format!(
+						"day {:02} part {} {:?}: expected {}, got error {:?}",
+						day, part, example.name, expected, e
+					)),
+				}
+			}
+		}
+	}
+	assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// A part counts as implemented unless it panics from a `todo!()`, as in the day_00 template
+#[test]
+fn implemented_parts_have_examples() {
+	let mut missing: Vec<String> = Vec::new();
+	for day in solution_days!() {
+		let puzzle = solution_for(day);
+		let examples = puzzle.examples();
+		for part in [1, 2] {
+			if examples
+				.iter()
+				.any(|example| example.answer(part).is_some())
+			{
+				continue;
+			}
+			let input = examples.first().map_or("", |example| example.input);
+			let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+				run_part(&*puzzle, part, input)
+			}));
+			let unimplemented = outcome.is_err_and(|payload| {
+				let message = payload
+					.downcast_ref::<String>()
+					.map(String::as_str)
+					.or_else(|| payload.downcast_ref::<&str>().copied())
+					.unwrap_or_default();
+				message.starts_with("not yet implemented")
+			});
+			if !unimplemented {
+				missing.push(format!("day {:02} part {}", day, part));
+			}
+		}
+	}
+	assert!(
+		missing.is_empty(),
+		"Implemented parts without an example answer: {}",
+		missing.join(", ")
+	);
+}
@@ -17,11 +17,11 @@ impl Puzzle for Day00 {
 	fn part_two(&self, _input: &str) -> super::PuzzleResult {
 		todo!("implement part two")
 	}
-}
 
-#[test]
-fn sample_day00_1() {
-	let input = r#"
-"#;
-	println!("{:?}", Day00.part_one(input).unwrap());
+	fn examples(&self) -> Vec<Example> {
+		vec![Example::new(
+			"sample", r#"
+"#,
+		)]
+	}
 }
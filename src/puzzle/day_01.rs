@@ -6,29 +6,21 @@ pub struct Day01;
 
 type DataType = i32;
 
+/// The left and right lists, read down the two columns
+fn lists(input: &str) -> Result<(Vec<DataType>, Vec<DataType>), anyhow::Error> {
+    let Ok([left, right]) = <[Vec<DataType>; 2]>::try_from(int_columns(input)?) else {
+        anyhow::bail!("Expected two lists side by side");
+    };
+    Ok((left, right))
+}
+
 impl Puzzle for Day01 {
     fn new(_ops: &super::RootOpt) -> Box<dyn Puzzle> {
         Box::new(Self)
     }
 
     fn part_one(&self, _input: &str) -> super::PuzzleResult {
-        // dbg!(_input);
-        let (mut l1, mut l2): (Vec<_>, Vec<_>) = _input
-            .trim()
-            .lines()
-            .map(|l| {
-                // dbg!(l);
-                let chunks = l.split_whitespace().map(|n_str| {
-                    n_str
-                        .parse::<DataType>()
-                        .expect("Failed to unwrap data value")
-                });
-                // println!("{:?}", chunks.clone().collect_vec());
-                return chunks
-                    .collect_tuple::<(DataType, DataType)>()
-                    .expect("Wrong number of data-values");
-            })
-            .unzip();
+        let (mut l1, mut l2) = lists(_input)?;
 
         l1.sort();
         l2.sort();
@@ -40,22 +32,7 @@ impl Puzzle for Day01 {
     }
 
     fn part_two(&self, _input: &str) -> super::PuzzleResult {
-        let (left, right): (Vec<_>, Vec<_>) = _input
-            .trim()
-            .lines()
-            .map(|l| {
-                // dbg!(l);
-                let chunks = l.split_whitespace().map(|n_str| {
-                    n_str
-                        .parse::<DataType>()
-                        .expect("Failed to unwrap data value")
-                });
-                // println!("{:?}", chunks.clone().collect_vec());
-                return chunks
-                    .collect_tuple::<(DataType, DataType)>()
-                    .expect("Wrong number of data-values");
-            })
-            .unzip();
+        let (left, right) = lists(_input)?;
 
         let mut freq_sum: HashMap<DataType, i32> = HashMap::new();
         for r in right {
@@ -69,19 +46,26 @@ impl Puzzle for Day01 {
             .sum::<i32>();
         return Ok(result.to_string());
     }
-}
 
-impl Day01 {}
+    fn validations(&self) -> Vec<Validation> {
+        vec![Validation::AllowedChars("0123456789 ")]
+    }
 
-#[test]
-fn sample_1() {
-    let input = r#"
+    fn examples(&self) -> Vec<Example> {
+        vec![Example::new(
+            "sample",
+            r#"
 3   4
 4   3
 2   5
 1   3
 3   9
 3   3
-"#;
-    println!("{:?}", Day01.part_one(input).unwrap());
+"#,
+        )
+        .part_one("11")
+        .part_two("31")]
+    }
 }
+
+impl Day01 {}
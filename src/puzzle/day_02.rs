@@ -10,35 +10,20 @@ impl Puzzle for Day02 {
     }
 
     fn part_one(&self, _input: &str) -> super::PuzzleResult {
-        let reports = _input.trim().lines().map(|l| {
-            // dbg!(l);
-            let chunks = l.split_whitespace().map(|n_str| {
-                n_str
-                    .parse::<DataType>()
-                    .expect("Failed to unwrap data value")
-            });
-            // println!("{:?}", chunks.clone().collect_vec());
-            return chunks;
-        });
-        let safety_scores = reports.map(report_is_safe).filter(|s| s.clone());
+        let reports = int_rows::<DataType>(_input)?;
+        let safety_scores = reports
+            .iter()
+            .map(|report| report_is_safe(report.iter().copied()))
+            .filter(|s| s.clone());
         let count = safety_scores.count();
         return Ok(count.to_string());
     }
 
     fn part_two(&self, _input: &str) -> super::PuzzleResult {
-        let reports = _input.trim().lines().map(|l| {
-            // dbg!(l);
-            let chunks = l.split_whitespace().map(|n_str| {
-                n_str
-                    .parse::<DataType>()
-                    .expect("Failed to unwrap data value")
-            });
-            // println!("{:?}", chunks.clone().collect_vec());
-            return chunks;
-        });
+        let reports = int_rows::<DataType>(_input)?;
         let safety_scores = reports
-            .map(|iter| {
-                let values = iter.collect_vec();
+            .iter()
+            .map(|values| {
                 if report_is_safe(values.iter().copied()) {
                     return true;
                 }
@@ -50,6 +35,26 @@ impl Puzzle for Day02 {
         let count = safety_scores.count();
         return Ok(count.to_string());
     }
+
+    fn validations(&self) -> Vec<Validation> {
+        vec![Validation::AllowedChars("0123456789 ")]
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![Example::new(
+            "sample",
+            r#"
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+"#,
+        )
+        .part_one("2")
+        .part_two("4")]
+    }
 }
 
 struct SkippingIndexIterator<T, Itor>
@@ -117,30 +122,3 @@ fn report_is_safe(iter: impl Iterator<Item = DataType>) -> bool {
     }
     return true;
 }
-
-#[test]
-fn sample_02_1() {
-    let input = r#"
-7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9
-"#;
-
-    println!("{:?}", Day02.part_one(input).unwrap());
-}
-#[test]
-fn sample_02_2() {
-    let input = r#"
-7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9
-"#;
-
-    println!("{:?}", Day02.part_two(input).unwrap());
-}
@@ -10,7 +10,10 @@ use nom::{
 };
 use nom_supreme::error::ErrorTree;
 
+use crate::diagnostic::{input_name, Diagnostic};
 use crate::prelude::*;
+#[cfg(test)]
+use proptest::prelude::*;
 
 pub struct Day03;
 
@@ -31,21 +34,36 @@ impl Puzzle for Day03 {
     }
 
     fn part_two(&self, _input: &str) -> super::PuzzleResult {
-        Ok(switchable_extract_mul_chunks(_input)
+        Ok(switchable_extract_mul_chunks(_input)?
             .iter()
             .map(|(a, b)| a * b)
             .sum::<DataType>()
             .to_string())
         // todo!("bang")
     }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example::new(
+                "sample",
+                "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))",
+            )
+            .part_one("161"),
+            Example::new(
+                "sample with conditionals",
+                "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))",
+            )
+            .part_two("48"),
+        ]
+    }
 }
 
 fn extract_two_ints(input: &str) -> Result<OperationParams, anyhow::Error> {
     // find the stuff that's part of the command, and discard the rest
-    let mut call_boundary = input.split(")").take(2);
-    let params = call_boundary
-        .nth(0)
-        .expect("No closing paren present")
+    let Some((call, _)) = input.split_once(")") else {
+        bail!("No closing paren present");
+    };
+    let params = call
         .split(",")
         .take(2)
         .filter_map(|p| p.parse::<DataType>().ok())
@@ -72,7 +90,13 @@ fn sample_day03_1() {
     let input = r#"
 xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))
 "#;
-    println!("{:?}", extract_mul_chunks(input));
+    assert_eq!(
+        extract_mul_chunks(input),
+        vec![(2, 4), (5, 5), (11, 8), (8, 5)]
+    );
+    // A call the input ends before closing doesn't count
+    assert_eq!(extract_mul_chunks("mul(2,4)mul(3,5"), vec![(2, 4)]);
+    assert!(extract_two_ints("3,5").is_err());
 }
 
 fn int_parser<'a, E>(input: &'a str) -> IResult<&'a str, DataType, E>
@@ -113,6 +137,11 @@ where
     .parse(input)
 }
 
+/// Byte length of the first char, so we never slice through a multi-byte character
+fn first_char_len(input: &str) -> usize {
+    input.chars().next().map_or(0, char::len_utf8)
+}
+
 /// Flips a 2-tuple
 fn rev2<A, B>((a, b): (A, B)) -> (B, A) {
     (b, a)
@@ -127,39 +156,15 @@ where
         return IResult::Err(nom::Err::Incomplete(nom::Needed::Unknown));
     }
 
-    let min_index = ["do()", "don't()", "mul("]
+    // Look past the first char, or a malformed command at the start would hide the
+    // next well-formed one and we'd swallow the rest of the input as junk
+    let skip = first_char_len(input);
+    let junk_len = ["do()", "don't()", "mul("]
         .iter()
-        .map(|sub| {
-            let idx = input.find(sub).map_or(input.len(), |i| i);
-            // if idx == 1 {
-            //     log::error!("{} {}", idx, input);
-            // }
-            return idx;
-        })
-        .filter(|&i| i > 0)
-        // .map(|i| {
-        //     ///
-        //     ///
-        //     if i == 1 {
-        //         log::error!("{}", i);
-        //     }
-        //     i
-        // })
-        .min();
-
-    match min_index {
-        Some(the_min) => {
-            if the_min == 0 {
-                // If it's at the start, it should be handled by something else
-                return IResult::Err(nom::Err::Incomplete(nom::Needed::Unknown));
-            }
-            return IResult::Ok(rev2(input.split_at(the_min)));
-        }
-        None => {
-            // It's not obviously, safely junk, and it's not a known command, chomp one char
-            return IResult::Ok(rev2(input.split_at(1)));
-        }
-    };
+        .filter_map(|sub| input[skip..].find(sub))
+        .min()
+        .map_or(input.len(), |i| i + skip);
+    IResult::Ok(rev2(input.split_at(junk_len)))
 }
 fn disable_parser<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
 where
@@ -181,13 +186,24 @@ where
     preceded(tag("mul"), params_parser).parse(input)
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 enum Op {
     Junk,
     Disable,
     Enable,
     Mul(OperationParams),
 }
+impl std::fmt::Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // The junk's text isn't kept, any char that can't start a command will do
+            Op::Junk => write!(f, "?"),
+            Op::Disable => write!(f, "don't()"),
+            Op::Enable => write!(f, "do()"),
+            Op::Mul((a, b)) => write!(f, "mul({},{})", a, b),
+        }
+    }
+}
 fn op_parser<'a, E>(input: &'a str) -> IResult<&'a str, Op, E>
 where
     E: ParseError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
@@ -209,8 +225,10 @@ where
         return IResult::Ok((remain, Op::Junk));
     }
     // It's not obviously, safely junk, and it's not a known command, chomp one char
-    return IResult::Ok((&input[1..], Op::Junk));
+    return IResult::Ok((&input[first_char_len(input)..], Op::Junk));
 }
+/// Never fails: `op_parser` takes anything that isn't a command as `Op::Junk`, so the
+/// whole input always parses
 fn ops_parser<'a, E>(input: &'a str) -> IResult<&'a str, Vec<Op>, E>
 where
     E: ParseError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError> + std::fmt::Debug,
@@ -240,10 +258,11 @@ enum ParserState {
     ScanForMulOrDisable,
     ScanForEnable,
 }
-fn switchable_extract_mul_chunks(input: &str) -> Vec<OperationParams> {
+fn switchable_extract_mul_chunks(input: &str) -> Result<Vec<OperationParams>, Error> {
+    // ops_parser can't fail, since corrupted memory is all junk in between commands. The
+    // report is only here in case it ever learns to reject something.
     let (_, ops) = ops_parser::<ErrorTree<&str>>(input)
-        .ok()
-        .expect("Couldn't Parse Ops");
+        .map_err(|e| Diagnostic::from_nom(&input_name(Day03::DAY), input, e))?;
 
     // let (_, ops) = dbg!(tmp);
 
@@ -262,51 +281,124 @@ fn switchable_extract_mul_chunks(input: &str) -> Vec<OperationParams> {
             }
         };
     }
-    return remaining_muls;
+    return Ok(remaining_muls);
 }
 
 #[test]
 fn sample_day03_2() {
-    let _ = dbg!(tag::<&str, &str, ErrorTree<&str>>("don't()").parse("don't()forget to have fun1"));
-    let _ = dbg!(tag::<&str, &str, ErrorTree<&str>>("don't()").parse("expected fail don't()"));
-    let _ = dbg!(comma_parser::<ErrorTree<&str>>(" , expected success"));
-    let _ = dbg!(params_parser::<ErrorTree<&str>>("(1,2) expected success"));
-    let _ = dbg!(params_parser::<ErrorTree<&str>>("(1, expected failure"));
-    let _ = dbg!(disable_parser::<ErrorTree<&str>>(
-        "don't() expected success"
-    ));
-    let _ = dbg!(mul_parser::<ErrorTree<&str>>("mul(1,2) expected success"));
-    let _ = dbg!(op_parser::<ErrorTree<&str>>("do()expected success"));
-    let _ = dbg!(safe_junk_parser::<ErrorTree<&str>>("expected success"));
-    let _ = dbg!(safe_junk_parser::<ErrorTree<&str>>("expected do() success"));
-    let _ = dbg!(safe_junk_parser::<ErrorTree<&str>>("expected success"));
-    let _ = dbg!(ops_parser::<ErrorTree<&str>>("do()don't()mul(1,2)"));
-    let _ = dbg!(ops_parser::<ErrorTree<&str>>("do()don't()mul(1,2)mul["));
-    let _ = dbg!(ops_parser::<ErrorTree<&str>>("ado()don't()mul(1,2)mul["));
-    let _ = dbg!(ops_parser::<ErrorTree<&str>>(
-        "ado()mul(1,2don't()mul(1,2)mul["
-    ));
+    assert!(tag::<&str, &str, ErrorTree<&str>>("don't()")
+        .parse("don't()forget to have fun1")
+        .is_ok());
+    assert!(tag::<&str, &str, ErrorTree<&str>>("don't()")
+        .parse("expected fail don't()")
+        .is_err());
+    assert_eq!(
+        comma_parser::<ErrorTree<&str>>(" , expected success").unwrap(),
+        ("expected success", (" ", " "))
+    );
+    assert_eq!(
+        params_parser::<ErrorTree<&str>>("(1,2) expected success").unwrap(),
+        (" expected success", (1, 2))
+    );
+    assert!(params_parser::<ErrorTree<&str>>("(1, expected failure").is_err());
+    assert!(disable_parser::<ErrorTree<&str>>("don't() expected success").is_ok());
+    assert_eq!(
+        mul_parser::<ErrorTree<&str>>("mul(1,2) expected success").unwrap(),
+        (" expected success", (1, 2))
+    );
+    assert_eq!(
+        op_parser::<ErrorTree<&str>>("do()expected success").unwrap(),
+        ("expected success", Op::Enable)
+    );
+    assert_eq!(
+        safe_junk_parser::<ErrorTree<&str>>("expected success").unwrap(),
+        ("", "expected success")
+    );
+    assert_eq!(
+        safe_junk_parser::<ErrorTree<&str>>("expected do() success").unwrap(),
+        ("do() success", "expected ")
+    );
+    assert_eq!(
+        ops_parser::<ErrorTree<&str>>("do()don't()mul(1,2)").unwrap().1,
+        vec![Op::Enable, Op::Disable, Op::Mul((1, 2))]
+    );
+    assert_eq!(
+        ops_parser::<ErrorTree<&str>>("ado()don't()mul(1,2)mul[").unwrap().1,
+        vec![Op::Junk, Op::Enable, Op::Disable, Op::Mul((1, 2)), Op::Junk]
+    );
+    assert_eq!(
+        ops_parser::<ErrorTree<&str>>("ado()mul(1,2don't()mul(1,2)mul[").unwrap().1,
+        vec![Op::Junk, Op::Enable, Op::Junk, Op::Disable, Op::Mul((1, 2)), Op::Junk]
+    );
 
-    println!(
-        "{:?}",
-        switchable_extract_mul_chunks("mul(32,64]then(mul(11,8)mul(8,5))")
+    assert_eq!(
+        switchable_extract_mul_chunks(
+            "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"
+        )
+        .unwrap(),
+        vec![(2, 4), (8, 5)]
     );
-    let input = r#"
-        xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
-    "#
-    .trim();
-    println!(
-        "{:?} Result: {:?}",
-        switchable_extract_mul_chunks(input),
-        switchable_extract_mul_chunks(input)
-            .iter()
-            .map(|(a, b)| a * b)
-            .sum::<DataType>()
-            .to_string()
+}
+
+#[test]
+fn junk_stops_before_a_command_after_a_malformed_one() {
+    assert_eq!(
+        safe_junk_parser::<ErrorTree<&str>>("mul(32,64]then(mul(11,8)").unwrap(),
+        ("mul(11,8)", "mul(32,64]then(")
+    );
+    assert_eq!(
+        switchable_extract_mul_chunks("mul(32,64]then(mul(11,8)mul(8,5))").unwrap(),
+        vec![(11, 8), (8, 5)]
     );
+}
+
+/// The ops that carry meaning, since adjacent junk merges when re-parsed
+#[cfg(test)]
+fn meaningful_ops(input: &str) -> Vec<Op> {
+    let (_, ops) = ops_parser::<ErrorTree<&str>>(input).unwrap();
+    ops.into_iter().filter(|op| *op != Op::Junk).collect()
+}
 
-    // println!(
-    //     "{:?}",
-    //     switchable_extract_mul_chunks("mul(32,64]then(don't()mul(11,8)do()mul(8,5))")
-    // );
+#[cfg(test)]
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        Just(Op::Junk),
+        Just(Op::Disable),
+        Just(Op::Enable),
+        (0..1000 as DataType, 0..1000 as DataType).prop_map(Op::Mul),
+    ]
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn ops_parser_never_panics(input in any::<String>()) {
+        let _ = ops_parser::<ErrorTree<&str>>(&input);
+        let _ = switchable_extract_mul_chunks(&input);
+    }
+
+    #[test]
+    fn params_parser_never_panics(input in "\\(?[0-9 ,]{0,8}\\)?.{0,4}") {
+        let _ = params_parser::<ErrorTree<&str>>(&input);
+    }
+
+    #[test]
+    fn params_parser_reads_what_it_printed(a in 0..1000 as DataType, b in 0..1000 as DataType, pad in " {0,2}") {
+        let text = format!("({}{},{}{})", a, pad, pad, b);
+        prop_assert_eq!(params_parser::<ErrorTree<&str>>(&text).unwrap(), ("", (a, b)));
+    }
+
+    #[test]
+    fn ops_print_parse_round_trip(ops in proptest::collection::vec(op_strategy(), 0..20)) {
+        let text = ops.iter().join("");
+        let expected: Vec<Op> = ops.into_iter().filter(|op| *op != Op::Junk).collect();
+        prop_assert_eq!(meaningful_ops(&text), expected);
+    }
+
+    #[test]
+    fn ops_parse_print_parse_is_stable(input in any::<String>()) {
+        let first = meaningful_ops(&input);
+        let reprinted = first.iter().join("");
+        prop_assert_eq!(meaningful_ops(&reprinted), first);
+    }
 }
@@ -1,8 +1,12 @@
 use std::collections::VecDeque;
 
+use crate::diagnostic::{input_name, Diagnostic};
 use crate::prelude::*;
-use ariadne::{Color, Label, Report, ReportKind, Source};
 use chumsky::prelude::*;
+// Named imports, as chumsky's prelude also exports an `any`
+#[cfg(test)]
+use proptest::prelude::{prop_assert_eq, proptest, Strategy};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
 use strum_macros::Display;
 
 pub struct Day05;
@@ -13,7 +17,7 @@ impl Puzzle for Day05 {
 	}
 
 	fn part_one(&self, _input: &str) -> super::PuzzleResult {
-		let doc = parse(_input);
+		let doc = parse(_input)?;
 		Ok(doc
 			.middle_page_of_valid_updates()
 			.iter()
@@ -22,13 +26,142 @@ impl Puzzle for Day05 {
 	}
 
 	fn part_two(&self, _input: &str) -> super::PuzzleResult {
-		let doc = parse(_input);
+		let doc = parse(_input)?;
 		Ok(doc
 			.middle_page_of_invalid_updates()
 			.iter()
 			.sum::<DataType>()
 			.to_string())
 	}
+
+	fn validations(&self) -> Vec<Validation> {
+		vec![
+			Validation::AllowedChars("0123456789|,"),
+			Validation::NumberRange(0..=DataType::MAX as i64),
+		]
+	}
+
+	fn examples(&self) -> Vec<Example> {
+		vec![Example::new(
+			"sample",
+			r#"
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+"#,
+		)
+		.part_one("143")
+		.part_two("123")]
+	}
+
+	fn generator(&self) -> Option<&dyn InputGenerator> {
+		Some(self)
+	}
+
+	fn reference(&self, part: u8) -> Option<ReferenceFn> {
+		match part {
+			1 => Some(reference_part_one),
+			_ => Some(reference_part_two),
+		}
+	}
+}
+
+/// In order when no page has a rule saying it belongs before any page ahead of it
+fn reference_in_order(doc: &Document, update: &[DataType]) -> bool {
+	update
+		.iter()
+		.tuple_combinations()
+		.all(|(a, b)| !doc.order_rules.contains(&(*b, *a)))
+}
+
+fn reference_part_one(input: &str) -> super::PuzzleResult {
+	let doc = parse(input)?;
+	Ok(doc
+		.updates_section
+		.iter()
+		.filter(|update| reference_in_order(&doc, update))
+		.map(|update| update[update.len() / 2])
+		.sum::<DataType>()
+		.to_string())
+}
+
+/// Bubble sorts each out-of-order update, swapping neighbours while any pair breaks a rule
+fn reference_part_two(input: &str) -> super::PuzzleResult {
+	let doc = parse(input)?;
+	let mut total: DataType = 0;
+	for update in doc
+		.updates_section
+		.iter()
+		.filter(|update| !reference_in_order(&doc, update))
+	{
+		let mut update = update.clone();
+		while let Some(idx) =
+			(1..update.len()).find(|idx| doc.order_rules.contains(&(update[*idx], update[idx - 1])))
+		{
+			update.swap(idx - 1, idx);
+		}
+		total += update[update.len() / 2];
+	}
+	Ok(total.to_string())
+}
+
+impl InputGenerator for Day05 {
+	/// Rules for every pair of up to 24 two-digit pages (so any update has one valid order),
+	/// followed by `size` updates of odd length, roughly half of them already in order
+	fn generate(&self, size: usize, rng: &mut StdRng) -> String {
+		let mut pages = (10..=99).collect::<Vec<DataType>>();
+		pages.shuffle(rng);
+		pages.truncate(rng.gen_range(3..=24));
+
+		let mut order_rules = pages
+			.iter()
+			.enumerate()
+			.flat_map(|(idx, a)| pages[idx + 1..].iter().map(move |b| (*a, *b)))
+			.collect::<Vec<_>>();
+		order_rules.shuffle(rng);
+
+		let updates_section = (0..size.max(1))
+			.map(|_| {
+				let len = rng.gen_range(1..=(pages.len() + 1) / 2) * 2 - 1;
+				let mut update = pages.choose_multiple(rng, len).copied().collect::<Vec<_>>();
+				if rng.gen_bool(0.5) {
+					update.sort_by_key(|page| pages.iter().position(|p| p == page));
+				}
+				update
+			})
+			.collect();
+
+		Document {
+			order_rules,
+			updates_section,
+		}
+		.to_string()
+	}
 }
 type DataType = i32;
 #[derive(Debug, Display)]
@@ -40,11 +173,26 @@ enum Expr {
 	// PageUpdates(Vec<Vec<DataType>>),
 	Document(Document),
 }
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 struct Document {
 	order_rules: Vec<(DataType, DataType)>,
 	updates_section: Vec<Vec<DataType>>,
 }
+/// Prints in the same layout as the puzzle input
+impl std::fmt::Display for Document {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (a, b) in self.order_rules.iter() {
+			writeln!(f, "{}|{}", a, b)?;
+		}
+		writeln!(f)?;
+		let updates = self
+			.updates_section
+			.iter()
+			.map(|update| update.iter().join(","))
+			.join("\n");
+		write!(f, "{}", updates)
+	}
+}
 impl Document {
 	fn new(_order_rules: Vec<Vec<DataType>>, updates_section: Vec<Vec<DataType>>) -> Self {
 		let order_rules: Vec<(DataType, DataType)> = _order_rules
@@ -158,7 +306,10 @@ fn parser<'a>() -> impl Parser<
 	// Simple<&'a str>>
 	chumsky::extra::Err<Rich<'a, char>>,
 > {
-	let int = text::int(10).map(|s: &str| s.parse::<DataType>().unwrap());
+	let int = text::int(10).try_map(|s: &str, span| {
+		s.parse::<DataType>()
+			.map_err(|e| Rich::custom(span, format!("{}: {}", s, e)))
+	});
 
 	let page_order_rule = int.separated_by(just('|')).exactly(2).collect::<Vec<_>>();
 	// .map(|n| Box::new(Expr::PageOrderRule(n)));
@@ -183,106 +334,49 @@ fn convert_ast_into_doc(ast: &Expr) -> Result<Document, &str> {
 		// _ => Result::Err("No document"),
 	};
 }
-fn parse(input: &str) -> Document {
+fn parse(input: &str) -> Result<Document, Error> {
 	return match parser().parse(input).into_result() {
 		Ok(ast) => match convert_ast_into_doc(&ast) {
 			Ok(output) => {
-				println!("{:?}", output);
-				output
-			}
-			Err(eval_err) => {
-				println!("Evaluation error: {}", eval_err);
-				panic!("Invalid Document")
+				log::debug!("{:?}", output);
+				Ok(output)
 			}
+			Err(eval_err) => anyhow::bail!("Invalid Document, evaluation error: {}", eval_err),
 		},
 		Err(parse_errs) => {
-			// parse_errs
-			//     .into_iter()
-			//     .for_each(|e| println!("Parse error: {}", e));
-			parse_errs.into_iter().for_each(|e| {
-				Report::build(ReportKind::Error, (), e.span().start)
-					.with_message(e.to_string())
-					.with_label(
-						Label::new(e.span().into_range())
-							.with_message(e.reason().to_string())
-							.with_color(Color::Red),
-					)
-					.finish()
-					.print(Source::from(&input))
-					.unwrap()
-			});
-			panic!("Couldn't parse ast, cannot proceed")
+			Err(Diagnostic::from_rich(&input_name(Day05::DAY), input, parse_errs).into())
 		}
 	};
 }
 
-#[test]
-fn sample_day05_1() {
-	let input = r#"
-47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47
-"#
-	.trim();
-	println!("{:?}", Day05.part_one(input).unwrap());
+#[cfg(test)]
+fn document_strategy() -> impl Strategy<Value = Document> {
+	let page = 0..100 as DataType;
+	let rules = proptest::collection::vec((page.clone(), page.clone()), 1..20);
+	let updates = proptest::collection::vec(proptest::collection::vec(page, 1..8), 1..10);
+	(rules, updates).prop_map(|(order_rules, updates_section)| Document {
+		order_rules,
+		updates_section,
+	})
 }
-#[test]
-fn sample_day05_2() {
-	let input = r#"
-47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
 
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47
-"#
-	.trim();
-	println!("{:?}", Day05.part_two(input).unwrap());
+#[cfg(test)]
+proptest! {
+	#[test]
+	fn parser_never_panics(input in proptest::arbitrary::any::<String>()) {
+		let _ = parse(&input);
+	}
+
+	#[test]
+	fn parser_never_panics_on_near_misses(input in "[0-9|,\n]{0,40}") {
+		let _ = parse(&input);
+	}
+
+	#[test]
+	fn document_print_parse_round_trip(doc in document_strategy()) {
+		let printed = doc.to_string();
+		let reparsed = parse(&printed).unwrap();
+		prop_assert_eq!(&reparsed, &doc);
+		prop_assert_eq!(reparsed.to_string(), printed);
+	}
 }
@@ -1,159 +1,227 @@
 use crate::prelude::*;
 
-use grid::Grid;
+#[cfg(test)]
+use rand::SeedableRng;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
 
-pub struct Day06;
+pub struct Day06 {
+	/// How many threads part two spreads the candidate obstacles over
+	threads: usize,
+}
+impl Default for Day06 {
+	fn default() -> Self {
+		Self { threads: 1 }
+	}
+}
 
 impl Puzzle for Day06 {
-	fn new(_ops: &super::RootOpt) -> Box<dyn Puzzle> {
-		Box::new(Self)
+	fn new(ops: &super::RootOpt) -> Box<dyn Puzzle> {
+		Box::new(Self {
+			threads: ops.threads(),
+		})
 	}
 
 	fn part_one(&self, _input: &str) -> super::PuzzleResult {
-		let the_map = FBGrid::from_str(_input);
+		let the_map: FBGrid<CellValue> = FBGrid::parse(_input)?;
 
-		println!("{}", the_map.to_string());
+		log::debug!("{}", the_map);
 
 		let path_to_exit = the_map.exit_map();
-		println!("{}", path_to_exit.to_string());
+		log::debug!("{}", path_to_exit);
 
 		Ok(path_to_exit.find_any_visited().count().to_string())
 	}
 
 	fn part_two(&self, _input: &str) -> super::PuzzleResult {
-		let the_map = FBGrid::from_str(_input);
-		println!("{}", the_map.to_string());
+		let the_map: FBGrid<CellValue> = FBGrid::parse(_input)?;
+		log::debug!("{}", the_map);
 
 		let (exit_map, path_to_exit) = the_map
 			.exit_map_with_loops(true)
 			.expect("Couldn't find an exit without modifications");
-		println!("{}", exit_map.to_string());
+		log::debug!("{}", exit_map);
 
-		let start = path_to_exit[0].0;
+		let start = path_to_exit[0];
 		let mut unique_locations: std::collections::HashSet<GridCoord2> =
 			std::collections::HashSet::new();
-		let candidates = path_to_exit.iter().filter_map(|(loc, dir)| {
-			let mut modified_map = the_map.clone();
-			if let Some(next_loc) = dir.next_coord(*loc) {
-				if unique_locations.contains(&next_loc) {
+		let candidates: Vec<GridCoord2> = path_to_exit
+			.iter()
+			.filter_map(|(loc, dir)| {
+				let next_loc = dir.next_coord(*loc)?;
+				if !unique_locations.insert(next_loc) {
 					return None;
 				}
-				unique_locations.insert(next_loc);
-				if let Some(current) = exit_map.grid.get(next_loc.0, next_loc.1) {
-					if next_loc != start && current.can_insert_obstacle(*dir) {
-						modified_map.grid[next_loc] = CellValue::Obstacle;
-						return Some((next_loc, modified_map));
-					}
-				}
-			}
-			return None;
-		});
-
-		let maps_that_loop: Vec<(GridCoord2, FBGrid<CellValue>)> = candidates
-			.filter_map(|(loc, modified_map)| {
-				if modified_map.exit_map_with_loops(false).is_none() {
-					return Some((loc, modified_map));
-				}
-				return None;
+				let current = exit_map.grid.get(next_loc.0, next_loc.1)?;
+				(next_loc != start.0 && current.can_insert_obstacle(*dir)).then_some(next_loc)
 			})
 			.collect();
 
-		Ok(maps_that_loop.len().to_string())
+		// Each thread works through its share with one grid of headings, reused for every
+		// candidate, and treats the candidate as an obstacle rather than copying the map
+		let the_map = &the_map;
+		let chunk_len = candidates.len().div_ceil(self.threads).max(1);
+		let loop_count = std::thread::scope(|scope| {
+			candidates
+				.chunks(chunk_len)
+				.map(|chunk| {
+					scope.spawn(move || {
+						let mut seen = grid::Grid::new(the_map.grid.rows(), the_map.grid.cols());
+						chunk
+							.iter()
+							.filter(|loc| the_map.loops_with_obstacle(start, **loc, &mut seen))
+							.count()
+					})
+				})
+				.collect::<Vec<_>>()
+				.into_iter()
+				.map(|handle| handle.join().expect("Loop check thread panicked"))
+				.sum::<usize>()
+		});
+
+		Ok(loop_count.to_string())
+	}
+
+	fn validations(&self) -> Vec<Validation> {
+		vec![
+			Validation::RectangularGrid,
+			Validation::AllowedChars(".#^>v<"),
+		]
+	}
+
+	fn examples(&self) -> Vec<Example> {
+		vec![Example::new(
+			"sample",
+			r#"
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+"#,
+		)
+		.part_one("41")
+		.part_two("6")]
+	}
+
+	fn generator(&self) -> Option<&dyn InputGenerator> {
+		Some(self)
+	}
+
+	fn reference(&self, part: u8) -> Option<ReferenceFn> {
+		match part {
+			1 => Some(reference_part_one),
+			_ => Some(reference_part_two),
+		}
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, EnumString, Display, Default)]
-enum SerializedCellValue {
-	#[strum(serialize = ".")]
-	#[default]
-	Unknown,
+/// Walks the guard one step at a time, returning the cells visited before leaving the map,
+/// or `None` if the guard ends up somewhere it has already been facing the same way
+fn reference_walk(
+	the_map: &FBGrid<CellValue>,
+	extra_obstacle: Option<GridCoord2>,
+) -> Option<std::collections::HashSet<GridCoord2>> {
+	let mut current = the_map.find_start().expect("No starting location found");
+	let mut seen = std::collections::HashSet::new();
+	loop {
+		if !seen.insert(current) {
+			return None;
+		}
+		let Some(next_loc) = current.1.next_coord(current.0) else {
+			break;
+		};
+		match the_map.grid.get(next_loc.0, next_loc.1) {
+			None => break,
+			Some(CellValue::Obstacle) => current.1 = current.1.rt90(),
+			Some(_) if Some(next_loc) == extra_obstacle => current.1 = current.1.rt90(),
+			Some(_) => current.0 = next_loc,
+		}
+	}
+	Some(seen.into_iter().map(|(loc, _)| loc).collect())
+}
 
-	#[strum(serialize = "{0}")]
-	Visited(String),
-	#[strum(serialize = "#")]
-	Obstacle,
-	#[strum(serialize = "^")]
-	PlayerFacingNorth,
-	#[strum(serialize = ">")]
-	PlayerFacingEast,
-	#[strum(serialize = "<")]
-	PlayerFacingWest,
-	#[strum(serialize = "v")]
-	PlayerFacingSouth,
+fn reference_part_one(input: &str) -> super::PuzzleResult {
+	let the_map = FBGrid::parse(input)?;
+	let visited =
+		reference_walk(&the_map, None).ok_or(anyhow::anyhow!("The guard never leaves"))?;
+	Ok(visited.len().to_string())
+}
+
+/// Tries an obstacle on every empty cell of the map
+fn reference_part_two(input: &str) -> super::PuzzleResult {
+	let the_map = FBGrid::parse(input)?;
+	let loops = the_map
+		.grid
+		.indexed_iter()
+		.filter(|(_, cell)| **cell == CellValue::Unknown)
+		.filter(|(loc, _)| reference_walk(&the_map, Some(*loc)).is_none())
+		.count();
+	Ok(loops.to_string())
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Display, Default)]
+impl InputGenerator for Day06 {
+	/// A `size`×`size` map with about one obstacle in ten, redrawn until the guard can
+	/// walk off it (as both parts expect)
+	fn generate(&self, size: usize, rng: &mut StdRng) -> String {
+		for _ in 0..100 {
+			let text = random_guard_map(size, 0.1, rng);
+			let the_map = FBGrid::<CellValue>::parse(&text);
+			if the_map.is_ok_and(|map| map.exit_map_with_loops(false).is_some()) {
+				return text;
+			}
+		}
+		// With nothing in the way the guard always walks straight off
+		random_guard_map(size, 0.0, rng)
+	}
+}
+
+fn random_guard_map(size: usize, obstacle_chance: f64, rng: &mut StdRng) -> String {
+	let size = size.max(1);
+	let mut cells = (0..size * size)
+		.map(|_| match rng.gen_bool(obstacle_chance) {
+			true => '#',
+			false => '.',
+		})
+		.collect::<Vec<_>>();
+	let start = rng.gen_range(0..cells.len());
+	cells[start] = TravelDirection::cardinal().choose(rng).unwrap().arrow();
+	cells
+		.chunks(size)
+		.map(|row| row.iter().collect::<String>())
+		.join("\n")
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Display, Default, GridCell)]
 enum CellValue {
 	#[default]
+	#[cell('.')]
 	Unknown,
+	/// The headings the guard had here, and the sides of the cell the path runs out of
+	#[cell(render = visited_glyph)]
 	VisitedTraveling {
-		n: bool,
-		e: bool,
-		s: bool,
-		w: bool,
+		dirs: DirectionSet,
+		arms: DirectionSet,
 	},
+	#[cell('#')]
 	Obstacle,
+	#[cell('^' => TravelDirection::N, '>' => TravelDirection::E, 'v' => TravelDirection::S, '<' => TravelDirection::W)]
 	PlayerFacing(TravelDirection),
 }
-impl From<SerializedCellValue> for CellValue {
-	fn from(value: SerializedCellValue) -> Self {
-		match value {
-			SerializedCellValue::Unknown => CellValue::Unknown,
-			SerializedCellValue::Obstacle => CellValue::Obstacle,
-			SerializedCellValue::PlayerFacingNorth => CellValue::PlayerFacing(TravelDirection::N),
-			SerializedCellValue::PlayerFacingEast => CellValue::PlayerFacing(TravelDirection::E),
-			SerializedCellValue::PlayerFacingWest => CellValue::PlayerFacing(TravelDirection::S),
-			SerializedCellValue::PlayerFacingSouth => CellValue::PlayerFacing(TravelDirection::W),
-			_ => panic!("Unimplemented parsing map for symbol {:?}", value),
-		}
-	}
-}
-impl From<CellValue> for SerializedCellValue {
-	fn from(value: CellValue) -> Self {
-		match value {
-			CellValue::Unknown => SerializedCellValue::Unknown,
-			CellValue::VisitedTraveling { n, e, s, w } => match (n, e, s, w) {
-				// (true, true, true, true) => SerializedCellValue::Visited(String::from("✛")),
-				// (true, true, true, false) => SerializedCellValue::Visited(String::from("├")),
-				// (true, true, false, true) => SerializedCellValue::Visited(String::from("┴")),
-				// (true, true, false, false) => SerializedCellValue::Visited(String::from("└")),
-				// (true, false, true, true) => SerializedCellValue::Visited(String::from("┤")),
-				// (true, false, true, false) => SerializedCellValue::Visited(String::from("↕")),
-				// (true, false, false, true) => SerializedCellValue::Visited(String::from("┘")),
-				// (true, false, false, false) => SerializedCellValue::Visited(String::from("↑")),
-				// (false, true, true, true) => SerializedCellValue::Visited(String::from("┬")),
-				// (false, true, true, false) => SerializedCellValue::Visited(String::from("┌")),
-				// (false, true, false, true) => SerializedCellValue::Visited(String::from("↔")),
-				// (false, true, false, false) => SerializedCellValue::Visited(String::from("→")),
-				// (false, false, true, true) => SerializedCellValue::Visited(String::from("┐")),
-				// (false, false, true, false) => SerializedCellValue::Visited(String::from("↓")),
-				// (false, false, false, true) => SerializedCellValue::Visited(String::from("←")),
-				(true, true, true, true) => SerializedCellValue::Visited(String::from("✛")),
-				(true, true, true, false) => SerializedCellValue::Visited(String::from("✛")),
-				(true, true, false, true) => SerializedCellValue::Visited(String::from("✛")),
-				(true, true, false, false) => SerializedCellValue::Visited(String::from("✛")),
-				(true, false, true, true) => SerializedCellValue::Visited(String::from("✛")),
-				(true, false, true, false) => SerializedCellValue::Visited(String::from("↕")),
-				(true, false, false, true) => SerializedCellValue::Visited(String::from("✛")),
-				(true, false, false, false) => SerializedCellValue::Visited(String::from("↑")),
-				(false, true, true, true) => SerializedCellValue::Visited(String::from("✛")),
-				(false, true, true, false) => SerializedCellValue::Visited(String::from("✛")),
-				(false, true, false, true) => SerializedCellValue::Visited(String::from("↔")),
-				(false, true, false, false) => SerializedCellValue::Visited(String::from("→")),
-				(false, false, true, true) => SerializedCellValue::Visited(String::from("✛")),
-				(false, false, true, false) => SerializedCellValue::Visited(String::from("↓")),
-				(false, false, false, true) => SerializedCellValue::Visited(String::from("←")),
-				(false, false, false, false) => SerializedCellValue::Unknown,
-			},
-			CellValue::Obstacle => SerializedCellValue::Obstacle,
-			CellValue::PlayerFacing(dir) => match dir {
-				TravelDirection::N => SerializedCellValue::PlayerFacingNorth,
-				TravelDirection::E => SerializedCellValue::PlayerFacingEast,
-				TravelDirection::S => SerializedCellValue::PlayerFacingWest,
-				TravelDirection::W => SerializedCellValue::PlayerFacingSouth,
-				_ => panic!("Unimplemented player direction"),
-			},
+fn visited_glyph(cell: &CellValue) -> char {
+	let CellValue::VisitedTraveling { dirs, arms } = *cell else {
+		return '.';
+	};
+	match dirs.iter().collect::<Vec<_>>()[..] {
+		// Passed straight through once, so show which way
+		[dir] if arms.iter().all(|arm| arm == dir || arm == dir.opposite()) => {
+			DirectionSet::from(dir).glyph()
 		}
+		_ => arms.glyph(),
 	}
 }
 impl CellValue {
@@ -161,36 +229,13 @@ impl CellValue {
 	fn can_insert_obstacle(&self, dir: TravelDirection) -> bool {
 		match self {
 			CellValue::Unknown => panic!("Useless to add an obstacle off the path"),
-			CellValue::VisitedTraveling { n, e, s, w } => match dir {
-				TravelDirection::N => n == &(dir == TravelDirection::N),
-				TravelDirection::E => e == &(dir == TravelDirection::E),
-				TravelDirection::S => s == &(dir == TravelDirection::S),
-				TravelDirection::W => w == &(dir == TravelDirection::W),
-				_ => false,
-			},
+			CellValue::VisitedTraveling { dirs, .. } => dirs.contains(dir),
 			CellValue::Obstacle => false,
 			CellValue::PlayerFacing(_) => false,
 		}
 	}
 }
 impl FBGrid<CellValue> {
-	// fn new(grid: Grid<CellValue>) -> Self {
-	//     Self { grid }
-	// }
-	fn from_str(input: &str) -> Self {
-		let g_data = input
-			.trim()
-			.lines()
-			.map(|l| {
-				l.chars()
-					.map(|c| SerializedCellValue::from_str(&c.to_string()).unwrap())
-					.map(|c| c.into())
-					.collect::<Vec<CellValue>>()
-			})
-			.collect::<Vec<Vec<CellValue>>>();
-		let grid: Grid<CellValue> = grid_from_vec_vec(g_data);
-		Self { grid }
-	}
 	fn find_start(&self) -> Option<(GridCoord2, TravelDirection)> {
 		if let Some((loc, dir_source)) = self.grid.indexed_iter().find(|(_, val)| {
 			if let CellValue::PlayerFacing(_) = val {
@@ -210,50 +255,48 @@ impl FBGrid<CellValue> {
 			_ => false,
 		})
 	}
-	fn visited_for(val: CellValue, dir: TravelDirection) -> Result<CellValue, ()> {
-		let next_is_n = TravelDirection::N == dir;
-		let next_is_e = TravelDirection::E == dir;
-		let next_is_s = TravelDirection::S == dir;
-		let next_is_w = TravelDirection::W == dir;
-
-		match val {
-			CellValue::VisitedTraveling { n, e, s, w } => {
-				if n && next_is_n || e && next_is_e || s && next_is_s || w && next_is_w {
+	/// Errs if it detects a loop!
+	fn add_travel_direction_to_cell(
+		&mut self,
+		loc: GridCoord2,
+		dir: TravelDirection,
+	) -> Result<(), ()> {
+		match self.grid[loc] {
+			CellValue::VisitedTraveling { ref mut dirs, .. } => {
+				if !dirs.insert(dir) {
 					// Already had marked a direction so this is a loop!
 					return Result::Err(());
 				}
-				return Result::Ok(CellValue::VisitedTraveling {
-					n: n || next_is_n,
-					e: e || next_is_e,
-					s: s || next_is_s,
-					w: w || next_is_w,
-				});
 			}
 			CellValue::Obstacle => panic!("Cannot go through an obstacle!"),
-			_ => Result::Ok(CellValue::VisitedTraveling {
-				n: TravelDirection::N == dir,
-				e: TravelDirection::E == dir,
-				s: TravelDirection::S == dir,
-				w: TravelDirection::W == dir,
-			}),
+			_ => {
+				self.grid[loc] = CellValue::VisitedTraveling {
+					dirs: dir.into(),
+					arms: DirectionSet::default(),
+				}
+			}
 		}
+		return Result::Ok(());
 	}
-	/// Errs if it detects a loop!
-	fn add_travel_direction_to_cell(
-		&mut self,
-		loc: GridCoord2,
-		dir: TravelDirection,
-	) -> Result<(), ()> {
-		match FBGrid::visited_for(self.grid[loc], dir) {
-			Ok(val) => {
-				self.grid[loc] = val;
-				return Result::Ok(());
+	/// Draw the path out of `loc` towards `arm`, marking the cell as visited if needed
+	fn add_arm_to_cell(&mut self, loc: GridCoord2, arm: TravelDirection) {
+		match self.grid[loc] {
+			CellValue::VisitedTraveling { ref mut arms, .. } => {
+				arms.insert(arm);
+			}
+			CellValue::Obstacle => panic!("Cannot go through an obstacle!"),
+			_ => {
+				self.grid[loc] = CellValue::VisitedTraveling {
+					dirs: DirectionSet::default(),
+					arms: arm.into(),
+				}
 			}
-			Err(e) => Result::Err(e),
 		}
 	}
 	/// Create a new map where we've marked all the paths we'd take to exit the maze
 	/// -- Abort if we detect a loop
+	/// -- With `collect_path`, also return the route & draw its arms for display; without,
+	///    the map is only good for checking where the guard went
 	fn exit_map_with_loops(
 		&self,
 		collect_path: bool,
@@ -290,6 +333,10 @@ impl FBGrid<CellValue> {
 					}
 					_ => {
 						// advance forwards if it's not an obstacle
+						if collect_path {
+							result.add_arm_to_cell(current.0, current.1);
+							result.add_arm_to_cell(next_loc, current.1.opposite());
+						}
 						current.0 = next_loc
 					}
 				}
@@ -301,57 +348,61 @@ impl FBGrid<CellValue> {
 		// When you exit off the top/left, we don't mark the cell as visited
 		let _ = result.add_travel_direction_to_cell(current.0, current.1);
 		if collect_path {
+			result.add_arm_to_cell(current.0, current.1);
 			path.push(current);
 		}
 
-		println!("last: {:?}", current);
 		return Some((result, path));
 	}
-	fn exit_map(&self) -> Self {
-		return self.exit_map_with_loops(false).expect("No exit found").0;
-	}
-	fn to_string(&self) -> String {
-		// let gs = self.grid.to_string()
-		let mut result = String::new();
-		for row in self.grid.iter_rows() {
-			for cell in row {
-				result.push_str(&SerializedCellValue::from(*cell).to_string());
+	/// Whether the guard walks in a loop once `obstacle` is added. The headings it has had in
+	/// each cell go in `seen`, which is cleared first, so the map itself is left alone.
+	fn loops_with_obstacle(
+		&self,
+		start: (GridCoord2, TravelDirection),
+		obstacle: GridCoord2,
+		seen: &mut grid::Grid<DirectionSet>,
+	) -> bool {
+		seen.fill(DirectionSet::default());
+		let (mut loc, mut dir) = start;
+		loop {
+			if !seen[loc].insert(dir) {
+				// Already been here facing this way
+				return true;
+			}
+			let Some(next_loc) = dir.next_coord(loc) else {
+				return false;
+			};
+			match self.grid.get(next_loc.0, next_loc.1) {
+				None => return false,
+				Some(CellValue::Obstacle) => dir = dir.rt90(),
+				Some(_) if next_loc == obstacle => dir = dir.rt90(),
+				Some(_) => loc = next_loc,
 			}
-			result.push('\n');
 		}
-		return result;
+	}
+	fn exit_map(&self) -> Self {
+		return self.exit_map_with_loops(true).expect("No exit found").0;
 	}
 }
+
 #[test]
-fn sample_day06_1() {
-	let input = r#"
-....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...
-"#;
-	println!("{:?}", Day06.part_one(input).unwrap());
+fn snapshot_day06_exit_map() {
+	let the_map = FBGrid::<CellValue>::parse(Day06::default().examples()[0].input).unwrap();
+	crate::testing::assert_snapshot("day06_map", &the_map.to_string());
+	crate::testing::assert_snapshot("day06_exit_map", &the_map.exit_map().to_string());
 }
 
 #[test]
-fn sample_day06_2() {
-	let input = r#"
-....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...
-"#;
-	println!("{:?}", Day06.part_two(input).unwrap());
+fn part_two_is_independent_of_thread_count() {
+	let mut rng = StdRng::seed_from_u64(6);
+	for _ in 0..4 {
+		let input = Day06::default().generate(16, &mut rng);
+		let serial = Day06 { threads: 1 }.part_two(&input).unwrap();
+		assert_eq!(Day06 { threads: 5 }.part_two(&input).unwrap(), serial);
+	}
+}
+
+#[test]
+fn grid_print_parse_round_trip() {
+	crate::testing::check_grid_round_trip::<CellValue>(".#^>v<");
 }
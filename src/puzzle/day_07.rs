@@ -1,7 +1,11 @@
+use crate::diagnostic::{input_name, Diagnostic};
 #[allow(dead_code)]
 use crate::prelude::*;
-use ariadne::{Color, Label, Report, ReportKind, Source};
 use chumsky::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+// Named imports, as chumsky's prelude also exports an `any`
+#[cfg(test)]
+use proptest::prelude::{prop_assert_eq, proptest, Strategy};
 use std::borrow::Borrow;
 use std::borrow::Cow;
 
@@ -13,8 +17,8 @@ impl Puzzle for Day07 {
 	}
 
 	fn part_one(&self, _input: &str) -> super::PuzzleResult {
-		let equation_list = parse(_input);
-		println!(
+		let equation_list = parse(_input)?;
+		log::debug!(
 			"{}",
 			equation_list.iter().map(|eq| eq.to_string()).join("\n")
 		);
@@ -22,8 +26,8 @@ impl Puzzle for Day07 {
 			.iter()
 			.filter(|eq| eq.is_solvable_recursive(false))
 			.collect();
-		let _ = dbg!(solvable_equations.len());
-		println!(
+		log::debug!("{} solvable", solvable_equations.len());
+		log::debug!(
 			"{}",
 			solvable_equations
 				.iter()
@@ -38,8 +42,8 @@ impl Puzzle for Day07 {
 	}
 
 	fn part_two(&self, _input: &str) -> super::PuzzleResult {
-		let equation_list = parse(_input);
-		println!(
+		let equation_list = parse(_input)?;
+		log::debug!(
 			"{}",
 			equation_list.iter().map(|eq| eq.to_string()).join("\n")
 		);
@@ -47,8 +51,8 @@ impl Puzzle for Day07 {
 			.iter()
 			.filter(|eq| eq.is_solvable_recursive(true))
 			.collect();
-		let _ = dbg!(solvable_equations.len());
-		println!(
+		log::debug!("{} solvable", solvable_equations.len());
+		log::debug!(
 			"{}",
 			solvable_equations
 				.iter()
@@ -61,6 +65,93 @@ impl Puzzle for Day07 {
 			.sum::<DataType>()
 			.to_string());
 	}
+
+	fn validations(&self) -> Vec<Validation> {
+		vec![
+			Validation::AllowedChars("0123456789: "),
+			Validation::NumberRange(0..=i64::MAX),
+		]
+	}
+
+	fn examples(&self) -> Vec<Example> {
+		vec![Example::new(
+			"sample",
+			r#"
+190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20
+"#,
+		)
+		.part_one("3749")
+		.part_two("11387")]
+	}
+
+	fn generator(&self) -> Option<&dyn InputGenerator> {
+		Some(self)
+	}
+
+	fn reference(&self, part: u8) -> Option<ReferenceFn> {
+		match part {
+			1 => Some(|input| reference_total(input, &[Operator::Add, Operator::Mul])),
+			_ => Some(|input| {
+				reference_total(input, &[Operator::Add, Operator::Mul, Operator::Concat])
+			}),
+		}
+	}
+}
+
+/// Tries every combination of `operators` between the numerals, evaluated left to right
+fn reference_total(input: &str, operators: &[Operator]) -> super::PuzzleResult {
+	let equation_list = parse(input)?;
+	let total = equation_list
+		.iter()
+		.filter(|eq| {
+			let gaps = eq.numerals.len() as u32 - 1;
+			(0..operators.len().pow(gaps)).any(|combination| {
+				let mut choice = combination;
+				let value = eq.numerals[1..].iter().fold(eq.numerals[0], |accum, n| {
+					let op = operators[choice % operators.len()];
+					choice /= operators.len();
+					op.apply(accum, n)
+				});
+				value == eq.result
+			})
+		})
+		.map(|eq| eq.result)
+		.sum::<DataType>();
+	Ok(total.to_string())
+}
+
+impl InputGenerator for Day07 {
+	/// `size` equations of 2-6 numbers below 100. About half get a result that some
+	/// combination of `+`, `*` & `||` reaches, the rest a random one.
+	fn generate(&self, size: usize, rng: &mut StdRng) -> String {
+		(0..size.max(1))
+			.map(|_| {
+				let len = rng.gen_range(2..=6);
+				let numbers = (0..len)
+					.map(|_| rng.gen_range(1..=99))
+					.collect::<Vec<DataType>>();
+				let result = if rng.gen_bool(0.5) {
+					numbers[1..].iter().fold(numbers[0], |acc, n| {
+						[Operator::Add, Operator::Mul, Operator::Concat]
+							.choose(rng)
+							.unwrap()
+							.apply(acc, n)
+					})
+				} else {
+					rng.gen_range(1..=1_000_000)
+				};
+				format!("{}: {}", result, numbers.iter().join(" "))
+			})
+			.join("\n")
+	}
 }
 type DataType = u64;
 #[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter, EnumString, Display, Hash)]
@@ -144,7 +235,7 @@ impl<'a> TestEquation<'a> {
 		return accum;
 	}
 	fn is_valid(&self) -> bool {
-		println!("Testing {}", self.to_string());
+		log::debug!("Testing {}", self.to_string());
 		let mut accum: DataType = 1;
 		let mut op = &Operator::Mul;
 		for (idx, current) in self.numerals.iter().enumerate() {
@@ -213,7 +304,10 @@ fn num_reachable_via_add_mul_concat(
 
 fn parser<'a>(
 ) -> impl Parser<'a, &'a str, Vec<TestEquation<'a>>, chumsky::extra::Err<Rich<'a, char>>> {
-	let int = text::int(10).map(|s: &str| s.parse::<DataType>().unwrap());
+	let int = text::int(10).try_map(|s: &str, span| {
+		s.parse::<DataType>()
+			.map_err(|e| Rich::custom(span, format!("{}: {}", s, e)))
+	});
 
 	let numerals = int.separated_by(just(' ')).at_least(1).collect::<Vec<_>>();
 	let equation = int
@@ -221,41 +315,64 @@ fn parser<'a>(
 		.then(numerals)
 		.map(|(result, numerals)| TestEquation::new(result, numerals.clone()));
 
-	let problem_list = equation.separated_by(just('\n')).collect::<Vec<_>>();
+	let problem_list = equation
+		.separated_by(just('\n'))
+		.collect::<Vec<_>>()
+		.padded();
 	return problem_list;
 }
-fn parse(input: &str) -> Vec<TestEquation<'_>> {
-	match parser().parse(input.trim()).into_result() {
-		Ok(equation_list) => equation_list,
-		Err(parse_errs) => {
-			parse_errs.into_iter().for_each(|e| {
-				Report::build(ReportKind::Error, (), e.span().start)
-					.with_message(e.to_string())
-					.with_label(
-						Label::new(e.span().into_range())
-							.with_message(e.reason().to_string())
-							.with_color(Color::Red),
-					)
-					.finish()
-					.print(Source::from(&input))
-					.unwrap()
-			});
-			panic!("Couldn't parse ast, cannot proceed")
-		}
-	}
+fn parse(input: &str) -> Result<Vec<TestEquation<'_>>, Error> {
+	// Leading whitespace is left to the parser, so reported lines & columns match the input
+	let source = input.trim_end();
+	parser().parse(source).into_result().map_err(|parse_errs| {
+		Diagnostic::from_rich(&input_name(Day07::DAY), source, parse_errs).into()
+	})
 }
+
 #[test]
-fn sample_day07_1() {
-	let input = r#"
-190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20
-"#;
-	println!("{:?}", Day07.part_one(input).unwrap());
+fn parse_errors_point_at_the_input_line() {
+	// The blank lines before the equations still count
+	let Err(err) = parse("\n\n190: 10 19\n3267 81 40 27") else {
+		panic!("expected a parse error");
+	};
+	let diagnostic = err.downcast_ref::<Diagnostic>().unwrap();
+	assert!(
+		diagnostic.rendered.contains("input/day07.txt:4:6"),
+		"{}",
+		diagnostic.rendered
+	);
+}
+
+#[cfg(test)]
+fn equation_list_strategy() -> impl Strategy<Value = Vec<(DataType, Vec<DataType>)>> {
+	let numerals = proptest::collection::vec(0..1000 as DataType, 1..6);
+	proptest::collection::vec((0..1_000_000_000_000 as DataType, numerals), 1..10)
+}
+
+#[cfg(test)]
+proptest! {
+	#[test]
+	fn parser_never_panics(input in proptest::arbitrary::any::<String>()) {
+		let _ = parse(&input);
+	}
+
+	#[test]
+	fn parser_never_panics_on_near_misses(input in "[0-9: \n]{0,40}") {
+		let _ = parse(&input);
+	}
+
+	#[test]
+	fn equation_print_parse_round_trip(equations in equation_list_strategy()) {
+		let printed = equations
+			.iter()
+			.map(|(result, numerals)| TestEquation::new(*result, numerals.clone()).to_string())
+			.join("\n");
+		let reparsed = parse(&printed).unwrap();
+		let reread = reparsed
+			.iter()
+			.map(|eq| (eq.result, eq.numerals.to_vec()))
+			.collect::<Vec<_>>();
+		prop_assert_eq!(&reread, &equations);
+		prop_assert_eq!(reparsed.iter().map(|eq| eq.to_string()).join("\n"), printed);
+	}
 }
@@ -1,95 +1,157 @@
 use crate::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
 use std::collections::{HashMap, HashSet};
 
 pub struct Day08;
 
+const ANTENNA_MAP_CHARS: &str = ".0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
 impl Puzzle for Day08 {
 	fn new(_ops: &super::RootOpt) -> Box<dyn Puzzle> {
 		Box::new(Self)
 	}
 
 	fn part_one(&self, _input: &str) -> super::PuzzleResult {
-		let radios = RadioMap::new(_input, PuzzlePart::P1);
-		println!("{}", radios.to_string());
+		let radios = RadioMap::new(_input, PuzzlePart::P1)?;
+		log::debug!("{}", radios.to_string());
 		return Ok(radios.antinode_locations.len().to_string());
 	}
 
 	fn part_two(&self, _input: &str) -> super::PuzzleResult {
-		let radios = RadioMap::new(_input, PuzzlePart::P2);
-		println!("{}", radios.to_string());
+		let radios = RadioMap::new(_input, PuzzlePart::P2)?;
+		log::debug!("{}", radios.to_string());
 		return Ok(radios.antinode_locations.len().to_string());
 	}
-}
 
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub enum CellConfigValue {
-	#[default]
-	Unknown,
-	Antenna(char),
-	Obstacle,
-}
-impl std::str::FromStr for CellConfigValue {
-	type Err = ();
+	fn validations(&self) -> Vec<Validation> {
+		vec![
+			Validation::RectangularGrid,
+			Validation::AllowedChars(ANTENNA_MAP_CHARS),
+		]
+	}
 
-	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		match s {
-			"" => Ok(CellConfigValue::Unknown),
-			"." => Ok(CellConfigValue::Unknown),
-			_ => Ok(CellConfigValue::Antenna(s.chars().nth(0).unwrap())),
-		}
+	fn examples(&self) -> Vec<Example> {
+		vec![
+			Example::new(
+				"sample",
+				r#"
+............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............
+"#,
+			)
+			.part_one("14")
+			.part_two("34"),
+			// Antennas 6 down & 4 across, so steps of (3, 2) also land on the cell between them
+			Example::new(
+				"gcd step",
+				r#"
+G....
+.....
+.....
+.....
+.....
+.....
+....G
+"#,
+			)
+			.part_one("0")
+			.part_two("3"),
+		]
 	}
-}
-impl CellConfigValue {
-	fn to_string(&self) -> String {
-		match self {
-			CellConfigValue::Unknown => String::from("."),
-			CellConfigValue::Antenna(c) => c.to_string(),
-			CellConfigValue::Obstacle => String::from("#"),
+
+	fn generator(&self) -> Option<&dyn InputGenerator> {
+		Some(self)
+	}
+
+	fn reference(&self, part: u8) -> Option<ReferenceFn> {
+		match part {
+			1 => Some(|input| reference_antinodes(input, PuzzlePart::P1)),
+			_ => Some(|input| reference_antinodes(input, PuzzlePart::P2)),
 		}
 	}
 }
 
-fn parse_map(input: &str) -> FBGrid<CellConfigValue> {
-	let parsed = input
-		.trim()
-		.split("\n")
-		.map(|line| {
-			line.chars()
-				.filter_map(|c| CellConfigValue::from_str(&c.to_string()).ok())
-				.collect::<Vec<_>>()
+/// Checks every cell against every pair of same-frequency antennas
+fn reference_antinodes(input: &str, part: PuzzlePart) -> super::PuzzleResult {
+	let grid: FBGrid<CellConfigValue> = FBGrid::parse(input)?;
+	let antennas = grid
+		.grid
+		.indexed_iter()
+		.filter_map(|(loc, val)| match val {
+			CellConfigValue::Antenna(c) => Some((*c, Vec2::from(loc))),
+			_ => None,
 		})
 		.collect::<Vec<_>>();
-	return FBGrid {
-		grid: grid_from_vec_vec(parsed),
+	let is_antinode = |p: Vec2| {
+		antennas
+			.iter()
+			.tuple_combinations()
+			.filter(|(a, b)| a.0 == b.0)
+			.any(|((_, a), (_, b))| match part {
+				// Twice as far from one antenna as the other, in line with both
+				PuzzlePart::P1 => p == *a * 2 - *b || p == *b * 2 - *a,
+				PuzzlePart::P2 => {
+					let (along, to) = (*b - *a, p - *a);
+					along.y * to.x == along.x * to.y
+				}
+			})
 	};
+	let count = grid
+		.grid
+		.indexed_iter()
+		.filter(|(loc, _)| is_antinode(Vec2::from(*loc)))
+		.count();
+	Ok(count.to_string())
 }
-impl FBGrid<CellConfigValue> {
-	fn to_string(&self) -> String {
-		// let gs = self.grid.to_string()
-		let mut result = String::new();
-		for row in self.grid.iter_rows() {
-			for cell in row {
-				result.push_str(&cell.to_string());
-			}
-			result.push('\n');
+
+impl InputGenerator for Day08 {
+	/// A `size`×`size` map with 1-4 frequencies of 2-4 antennas each, anywhere
+	fn generate(&self, size: usize, rng: &mut StdRng) -> String {
+		let size = size.max(2);
+		let frequencies = ANTENNA_MAP_CHARS[1..].chars().collect::<Vec<_>>();
+		let mut cells = vec!['.'; size * size];
+		let mut free = (0..cells.len()).collect::<Vec<_>>();
+		free.shuffle(rng);
+		let frequency_count = rng.gen_range(1..=4);
+		for frequency in frequencies.choose_multiple(rng, frequency_count) {
+			let wanted = rng.gen_range(2..=4).min(free.len());
+			free.drain(..wanted).for_each(|idx| cells[idx] = *frequency);
 		}
-		return result;
+		cells
+			.chunks(size)
+			.map(|row| row.iter().collect::<String>())
+			.join("\n")
 	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, GridCell)]
+pub enum CellConfigValue {
+	#[default]
+	#[cell('.')]
+	Unknown,
+	#[cell(matching = char::is_ascii_alphanumeric)]
+	Antenna(char),
+	#[cell('#')]
+	Obstacle,
+}
+impl FBGrid<CellConfigValue> {
 	fn make_antinode_left(
 		&self,
 		a: &GridCoord2,
 		_b: &GridCoord2,
 		dist: GridDistance2<i32>,
 	) -> Option<GridCoord2> {
-		let r0 = (a.0 as i32).checked_sub(dist.0)?;
-		let r1 = (a.1 as i32).checked_sub(dist.1)?;
-		if r0 < 0 || r1 < 0 {
-			return None;
-		}
-		if r0 as usize > self.grid.rows() || r1 as usize > self.grid.cols() {
-			return None;
-		}
-		return Some((r0 as usize, r1 as usize));
+		self.coord(Vec2::from(*a) - Vec2::from(dist))
 	}
 	fn make_antinode_right(
 		&self,
@@ -97,15 +159,7 @@ impl FBGrid<CellConfigValue> {
 		b: &GridCoord2,
 		dist: GridDistance2<i32>,
 	) -> Option<GridCoord2> {
-		let r0 = (b.0 as i32) + (dist.0);
-		let r1 = (b.1 as i32) + (dist.1);
-		if r0 < 0 || r1 < 0 {
-			return None;
-		}
-		if r0 as usize > self.grid.rows() || r1 as usize > self.grid.cols() {
-			return None;
-		}
-		return Some((r0 as usize, r1 as usize));
+		self.coord(Vec2::from(*b) + Vec2::from(dist))
 	}
 }
 struct RadioMap {
@@ -118,8 +172,8 @@ enum PuzzlePart {
 	P2,
 }
 impl RadioMap {
-	fn new(input: &str, part: PuzzlePart) -> Self {
-		let grid = parse_map(input);
+	fn new(input: &str, part: PuzzlePart) -> Result<Self, InputError> {
+		let grid = FBGrid::parse(input)?;
 		let mut antenna_locations: HashMap<char, Vec<GridCoord2>> = HashMap::new();
 		let mut antinode_locations: HashSet<GridCoord2> = HashSet::new();
 
@@ -148,14 +202,7 @@ impl RadioMap {
 								]
 							})
 							.flatten()
-							.filter_map(|n| {
-								if let Some(coord) = n {
-									if let Some(_) = grid.grid.get(coord.0, coord.1) {
-										return n;
-									}
-								}
-								return None;
-							})
+							.flatten()
 							.collect::<Vec<GridCoord2>>();
 					})
 					.flatten()
@@ -171,37 +218,15 @@ impl RadioMap {
 							.iter()
 							.combinations(2)
 							.map(|combo| {
-								let dist = grid.distance(combo[0], combo[1]).normalize();
-								let mut curr = *combo[0];
-								let mut batch: Vec<GridCoord2> = Vec::new();
-
-								// Include the starting node in the batch as make_antinode_left and make_antinode_right would skip it
-								batch.push(curr);
-								while let Some(next) =
-									grid.make_antinode_left(&curr, combo[1], dist)
-								{
-									batch.push(next);
-									curr = next;
-								}
-
-								// Go to the right from the left starting point to catch points between the antennas
-								curr = *combo[0];
-								while let Some(next) =
-									grid.make_antinode_right(combo[0], &curr, dist)
-								{
-									batch.push(next);
-									curr = next;
-								}
-
-								return batch;
+								let step =
+									Vec2::from(grid.distance(combo[0], combo[1]).normalize());
+								// Both ways along the line through the pair, the first antenna included
+								grid.ray_by(*combo[0], -step)
+									.chain(grid.ray_by(*combo[0], step).skip(1))
+									.map(|(loc, _)| loc)
+									.collect::<Vec<_>>()
 							})
 							.flatten()
-							.filter_map(|n| {
-								if let Some(_) = grid.grid.get(n.0, n.1) {
-									return Some(n);
-								}
-								return None;
-							})
 							.collect::<Vec<GridCoord2>>();
 					})
 					.flatten()
@@ -211,11 +236,11 @@ impl RadioMap {
 			}
 		}
 
-		return Self {
+		return Ok(Self {
 			grid,
 			// antenna_locations,
 			antinode_locations: antinode_locations.iter().map(|n| *n).collect::<Vec<_>>(),
-		};
+		});
 	}
 	fn to_string(&self) -> String {
 		let mut visual = self.grid.clone();
@@ -224,44 +249,20 @@ impl RadioMap {
 				*cell = CellConfigValue::Obstacle;
 			}
 		});
-		return format!("{}\n--\n{}", self.grid.to_string(), visual.to_string());
+		return format!("{}\n--\n{}", self.grid, visual);
 	}
 }
 
 #[test]
-fn sample_day08_1() {
-	let input = r#"
-............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............
-"#;
-	println!("{:?}", Day08.part_one(input).unwrap());
+fn snapshot_day08_antinodes() {
+	let input = Day08.examples()[0].input;
+	let part_one = RadioMap::new(input, PuzzlePart::P1).unwrap();
+	crate::testing::assert_snapshot("day08_antinodes_part_one", &part_one.to_string());
+	let part_two = RadioMap::new(input, PuzzlePart::P2).unwrap();
+	crate::testing::assert_snapshot("day08_antinodes_part_two", &part_two.to_string());
 }
 
 #[test]
-fn sample_day08_2() {
-	let input = r#"
-............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............
-"#;
-	println!("{:?}", Day08.part_two(input).unwrap());
+fn grid_print_parse_round_trip() {
+	crate::testing::check_grid_round_trip::<CellConfigValue>(".0aA");
 }
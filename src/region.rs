@@ -0,0 +1,233 @@
+//! Orthogonally connected groups of cells, and the measurements puzzles ask of them
+
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use crate::{Edges, FBGrid, GridCoord2, TravelDirection, Vec2};
+
+/// A non-empty set of connected cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+	cells: BTreeSet<GridCoord2>,
+	/// The grid's (rows, cols) if its edges wrap, so cells across the wrap are neighbours
+	wraps: Option<(usize, usize)>,
+}
+impl Region {
+	/// In reading order
+	pub fn cells(&self) -> impl Iterator<Item = GridCoord2> + '_ {
+		self.cells.iter().copied()
+	}
+	pub fn contains(&self, coord: GridCoord2) -> bool {
+		self.cells.contains(&coord)
+	}
+	/// Where `pos` lands once wrapped round the grid's edges, if they wrap
+	fn wrapped(&self, pos: Vec2) -> Vec2 {
+		match self.wraps {
+			Some((rows, cols)) => {
+				Vec2::new(pos.y.rem_euclid(rows as i64), pos.x.rem_euclid(cols as i64))
+			}
+			None => pos,
+		}
+	}
+	fn contains_vec(&self, pos: Vec2) -> bool {
+		GridCoord2::try_from(self.wrapped(pos)).is_ok_and(|coord| self.contains(coord))
+	}
+	/// Cell edges with a cell outside the region (or the grid's edge) on the other side
+	fn edges(&self) -> impl Iterator<Item = (Vec2, TravelDirection)> + '_ {
+		self.cells().flat_map(move |coord| {
+			TravelDirection::cardinal()
+				.into_iter()
+				.map(move |dir| (Vec2::from(coord), dir))
+				.filter(|(pos, dir)| !self.contains_vec(*pos + dir.delta()))
+		})
+	}
+
+	pub fn area(&self) -> usize {
+		self.cells.len()
+	}
+	/// How many unit edges separate the region from everything else, holes included
+	pub fn perimeter(&self) -> usize {
+		self.edges().count()
+	}
+	/// How many straight runs of fence the perimeter needs, holes included. A run that goes
+	/// all the way round a wrapping grid counts once.
+	pub fn sides(&self) -> usize {
+		let mut unfenced = self.edges().collect::<HashSet<_>>();
+		let mut sides = 0;
+		while let Some((pos, dir)) = unfenced.iter().next().copied() {
+			// Fence off the whole run this edge is part of, both ways along it
+			unfenced.remove(&(pos, dir));
+			for along in [dir.lt90(), dir.rt90()] {
+				let mut next = self.wrapped(pos + along.delta());
+				while unfenced.remove(&(next, dir)) {
+					next = self.wrapped(next + along.delta());
+				}
+			}
+			sides += 1;
+		}
+		sides
+	}
+	/// The top-left & bottom-right corners of the smallest rectangle around the region, in
+	/// grid coordinates even when the region carries on across a wrapping edge
+	pub fn bounding_box(&self) -> (GridCoord2, GridCoord2) {
+		// Reading order puts the top & bottom rows first & last
+		let (top, _) = self.cells.first().unwrap();
+		let (bottom, _) = self.cells.last().unwrap();
+		let left = self.cells().map(|(_, x)| x).min().unwrap();
+		let right = self.cells().map(|(_, x)| x).max().unwrap();
+		((*top, left), (*bottom, right))
+	}
+}
+
+impl<T> FBGrid<T>
+where
+	T: Default + PartialEq,
+{
+	/// The cells `include`d and connected to `start` through other `include`d cells,
+	/// or `None` if `start` itself isn't
+	pub fn flood_fill(&self, start: GridCoord2, include: impl Fn(&T) -> bool) -> Option<Region> {
+		if !include(self.grid.get(start.0, start.1)?) {
+			return None;
+		}
+		let mut cells = BTreeSet::from([start]);
+		let mut pending = VecDeque::from([start]);
+		while let Some(coord) = pending.pop_front() {
+			for (next, val) in self.neighbours4(coord) {
+				if include(val) && cells.insert(next) {
+					pending.push_back(next);
+				}
+			}
+		}
+		Some(Region {
+			cells,
+			wraps: self.region_wraps(),
+		})
+	}
+	fn region_wraps(&self) -> Option<(usize, usize)> {
+		(self.edges() == Edges::Wrapping).then_some((self.grid.rows(), self.grid.cols()))
+	}
+	/// Every connected group of `include`d cells, in reading order of their first cell
+	pub fn regions_where(&self, include: impl Fn(&T) -> bool) -> Vec<Region> {
+		let mut seen = BTreeSet::new();
+		let mut regions: Vec<Region> = Vec::new();
+		for (coord, val) in self.grid.indexed_iter() {
+			if include(val) && !seen.contains(&coord) {
+				let region = self.flood_fill(coord, &include).unwrap();
+				seen.extend(region.cells());
+				regions.push(region);
+			}
+		}
+		regions
+	}
+	/// Every connected group of equal cells, in reading order of their first cell
+	pub fn regions(&self) -> Vec<Region> {
+		let labels = self.region_labels();
+		let mut regions: Vec<BTreeSet<GridCoord2>> = Vec::new();
+		for (coord, label) in labels.grid.indexed_iter() {
+			match regions.get_mut(*label) {
+				Some(cells) => {
+					cells.insert(coord);
+				}
+				None => regions.push(BTreeSet::from([coord])),
+			}
+		}
+		regions
+			.into_iter()
+			.map(|cells| Region {
+				cells,
+				wraps: self.region_wraps(),
+			})
+			.collect()
+	}
+	/// The index in `regions()` of the region each cell belongs to
+	pub fn region_labels(&self) -> FBGrid<usize> {
+		let mut labels: FBGrid<Option<usize>> =
+			FBGrid::from(grid::Grid::new(self.grid.rows(), self.grid.cols()));
+		let mut next_label = 0;
+		for (coord, val) in self.grid.indexed_iter() {
+			if labels.grid[coord].is_some() {
+				continue;
+			}
+			let region = self.flood_fill(coord, |other| other == val).unwrap();
+			region
+				.cells()
+				.for_each(|cell| labels.grid[cell] = Some(next_label));
+			next_label += 1;
+		}
+		FBGrid::from(grid::Grid::from_vec(
+			labels.grid.into_vec().into_iter().flatten().collect(),
+			self.grid.cols(),
+		))
+		.with_edges(self.edges())
+	}
+}
+
+#[cfg(test)]
+fn garden(text: &str) -> FBGrid<char> {
+	let lines = text.trim().lines().collect::<Vec<_>>();
+	FBGrid::from(grid::Grid::from_vec(
+		lines.concat().chars().collect(),
+		lines[0].len(),
+	))
+}
+
+#[test]
+fn region_measurements() {
+	let grid = garden("AAAA\nBBCD\nBBCC\nEEEC");
+	let regions = grid.regions();
+	let measured = regions
+		.iter()
+		.map(|region| {
+			let (y, x) = region.cells().next().unwrap();
+			let plant = grid.grid[(y, x)];
+			(plant, region.area(), region.perimeter(), region.sides())
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(
+		measured,
+		vec![
+			('A', 4, 10, 4),
+			('B', 4, 8, 4),
+			('C', 4, 10, 8),
+			('D', 1, 4, 4),
+			('E', 3, 8, 4),
+		]
+	);
+	assert_eq!(regions[2].bounding_box(), ((1, 2), (3, 3)));
+
+	let labels = grid.region_labels();
+	assert_eq!(labels.grid[(3, 3)], 2);
+	assert_eq!(labels.grid[(1, 3)], 3);
+}
+
+#[test]
+fn regions_with_holes_count_inner_fences() {
+	let grid = garden("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO");
+	let outer = grid.flood_fill((0, 0), |plant| *plant == 'O').unwrap();
+	assert_eq!(
+		(outer.area(), outer.perimeter(), outer.sides()),
+		(21, 36, 20)
+	);
+	assert_eq!(outer.bounding_box(), ((0, 0), (4, 4)));
+	assert_eq!(grid.regions_where(|plant| *plant == 'X').len(), 4);
+	assert_eq!(grid.flood_fill((1, 1), |plant| *plant == 'O'), None);
+
+	let grid = garden("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE");
+	let e = grid.flood_fill((0, 0), |plant| *plant == 'E').unwrap();
+	assert_eq!((e.area(), e.sides()), (17, 12));
+}
+
+#[test]
+fn regions_join_across_wrapping_edges() {
+	let grid = garden("A.A\n...\nA.A").with_edges(Edges::Wrapping);
+	let corners = grid.flood_fill((0, 0), |plant| *plant == 'A').unwrap();
+	assert_eq!(
+		(corners.area(), corners.perimeter(), corners.sides()),
+		(4, 8, 4)
+	);
+	assert_eq!(corners.bounding_box(), ((0, 0), (2, 2)));
+
+	let grid = garden("BBB\n...\n...").with_edges(Edges::Wrapping);
+	let band = grid.flood_fill((0, 1), |plant| *plant == 'B').unwrap();
+	assert_eq!((band.area(), band.perimeter(), band.sides()), (3, 6, 2));
+	assert_eq!(grid.regions().len(), 2);
+}
@@ -0,0 +1,294 @@
+//! A grid with no fixed size, for worlds that grow as the puzzle runs or stretch into
+//! negative coordinates. Only occupied cells are stored.
+
+use std::collections::HashMap;
+
+use crate::line::bresenham;
+use crate::{FBGrid, GridCell, TravelDirection, Vec2};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseGrid<T> {
+	cells: HashMap<Vec2, T>,
+	/// Top-left & bottom-right occupied corners, both included
+	bounds: Option<(Vec2, Vec2)>,
+}
+impl<T> Default for SparseGrid<T> {
+	fn default() -> Self {
+		Self {
+			cells: HashMap::new(),
+			bounds: None,
+		}
+	}
+}
+impl<T> SparseGrid<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Returns what was there before
+	pub fn insert(&mut self, pos: Vec2, val: T) -> Option<T> {
+		self.bounds = Some(match self.bounds {
+			Some((min, max)) => (
+				Vec2::new(min.y.min(pos.y), min.x.min(pos.x)),
+				Vec2::new(max.y.max(pos.y), max.x.max(pos.x)),
+			),
+			None => (pos, pos),
+		});
+		self.cells.insert(pos, val)
+	}
+	pub fn remove(&mut self, pos: Vec2) -> Option<T> {
+		let removed = self.cells.remove(&pos)?;
+		let on_edge = self.bounds.is_some_and(|(min, max)| {
+			pos.y == min.y || pos.y == max.y || pos.x == min.x || pos.x == max.x
+		});
+		if on_edge {
+			self.bounds = self.cells.keys().fold(None, |bounds, pos| {
+				let (min, max) = bounds.unwrap_or((*pos, *pos));
+				Some((
+					Vec2::new(min.y.min(pos.y), min.x.min(pos.x)),
+					Vec2::new(max.y.max(pos.y), max.x.max(pos.x)),
+				))
+			});
+		}
+		Some(removed)
+	}
+	pub fn get(&self, pos: Vec2) -> Option<&T> {
+		self.cells.get(&pos)
+	}
+	pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+		self.cells.get_mut(&pos)
+	}
+	/// Whether anything is stored at `pos`
+	pub fn contains(&self, pos: Vec2) -> bool {
+		self.cells.contains_key(&pos)
+	}
+	pub fn len(&self) -> usize {
+		self.cells.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.cells.is_empty()
+	}
+	/// The top-left & bottom-right corners of the occupied cells, both included
+	pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+		self.bounds
+	}
+	/// Occupied cells, in no particular order
+	pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+		self.cells.iter().map(|(pos, val)| (*pos, val))
+	}
+
+	/// One step from `pos` towards `dir`. Unlike on an `FBGrid` there's no edge to fall off.
+	pub fn step(&self, pos: Vec2, dir: TravelDirection) -> Vec2 {
+		pos + dir.delta()
+	}
+	/// The occupied neighbours one step away in each of `dirs`,
+	/// along with the direction taken to reach them
+	pub fn directed_neighbours<'a>(
+		&'a self,
+		pos: Vec2,
+		dirs: impl IntoIterator<Item = TravelDirection> + 'a,
+	) -> impl Iterator<Item = (TravelDirection, Vec2, &'a T)> + 'a {
+		dirs.into_iter().filter_map(move |dir| {
+			let next = self.step(pos, dir);
+			Some((dir, next, self.get(next)?))
+		})
+	}
+	pub fn neighbours_in<'a>(
+		&'a self,
+		pos: Vec2,
+		dirs: impl IntoIterator<Item = TravelDirection> + 'a,
+	) -> impl Iterator<Item = (Vec2, &'a T)> + 'a {
+		self.directed_neighbours(pos, dirs)
+			.map(|(_, next, val)| (next, val))
+	}
+	/// The occupied orthogonal neighbours, clockwise from N
+	pub fn neighbours4(&self, pos: Vec2) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+		self.neighbours_in(pos, TravelDirection::cardinal())
+	}
+	/// The occupied orthogonal & diagonal neighbours, clockwise from N
+	pub fn neighbours8(&self, pos: Vec2) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+		self.neighbours_in(pos, TravelDirection::all())
+	}
+	/// The occupied cells exactly `radius` king's moves from `pos`, in reading order.
+	/// A radius of 0 is just `pos` itself.
+	pub fn ring(&self, pos: Vec2, radius: usize) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+		let r = radius as i64;
+		(-r..=r)
+			.flat_map(move |y| (-r..=r).map(move |x| Vec2::new(y, x)))
+			.filter(move |offset| offset.chebyshev() == radius as u64)
+			.filter_map(move |offset| {
+				let next = pos + offset;
+				Some((next, self.get(next)?))
+			})
+	}
+	/// The occupied cells from `start` towards `dir`, `start` included
+	pub fn ray(&self, start: Vec2, dir: TravelDirection) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+		self.ray_by(start, dir.delta())
+	}
+	/// The occupied cells reached from `start` by repeating `step`, `start` included. With no
+	/// edge to stop at, the ray ends once it can't come back into `bounds`.
+	pub fn ray_by(
+		&self,
+		start: Vec2,
+		step: impl Into<Vec2>,
+	) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+		let step = step.into();
+		let bounds = self.bounds;
+		std::iter::successors(Some(start), move |pos| {
+			(step != Vec2::default()).then_some(*pos + step)
+		})
+		.take_while(move |pos| {
+			bounds.is_some_and(|(min, max)| {
+				!(pos.y < min.y && step.y <= 0
+					|| pos.y > max.y && step.y >= 0
+					|| pos.x < min.x && step.x <= 0
+					|| pos.x > max.x && step.x >= 0)
+			})
+		})
+		.filter_map(move |pos| Some((pos, self.get(pos)?)))
+	}
+	/// The occupied cells on the Bresenham line from `from` to `to`, both ends included
+	pub fn line(&self, from: Vec2, to: Vec2) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+		bresenham(from, to).filter_map(move |pos| Some((pos, self.get(pos)?)))
+	}
+}
+impl<T: PartialEq> SparseGrid<T> {
+	/// Every cell equal to `needle`, with its position, in no particular order
+	pub fn find_iter(&self, needle: T) -> impl Iterator<Item = (Vec2, &T)> {
+		self.iter().filter(move |(_, val)| **val == needle)
+	}
+}
+
+/// Keeps every cell that isn't the default, at the same coordinates
+impl<T> From<FBGrid<T>> for SparseGrid<T>
+where
+	T: Default + PartialEq,
+{
+	fn from(dense: FBGrid<T>) -> Self {
+		let cols = dense.grid.cols();
+		let mut sparse = SparseGrid::new();
+		for (idx, val) in dense.grid.into_vec().into_iter().enumerate() {
+			if val != T::default() {
+				sparse.insert(Vec2::from((idx / cols, idx % cols)), val);
+			}
+		}
+		sparse
+	}
+}
+
+/// Renders the occupied bounding box, drawing empty cells as the default cell
+impl<T> std::fmt::Display for SparseGrid<T>
+where
+	T: GridCell + Default,
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let Some((min, max)) = self.bounds else {
+			return Ok(());
+		};
+		let blank = T::default().to_char();
+		for y in min.y..=max.y {
+			let row = (min.x..=max.x)
+				.map(|x| self.get(Vec2::new(y, x)).map_or(blank, GridCell::to_char))
+				.collect::<String>();
+			writeln!(f, "{}", row)?;
+		}
+		Ok(())
+	}
+}
+
+#[test]
+fn sparse_grid_grows_in_every_direction() {
+	#[derive(Debug, Default, Clone, PartialEq, macros::GridCell)]
+	enum Cell {
+		#[default]
+		#[cell('.')]
+		Empty,
+		#[cell('#')]
+		Rock,
+		#[cell('o')]
+		Sand,
+	}
+
+	let mut world = SparseGrid::from(FBGrid::<Cell>::parse("#.\n.o").unwrap());
+	assert_eq!(world.len(), 2);
+	assert_eq!(world.bounds(), Some((Vec2::new(0, 0), Vec2::new(1, 1))));
+
+	let above = world.step(Vec2::new(0, 0), TravelDirection::NW);
+	assert_eq!(world.insert(above, Cell::Sand), None);
+	assert_eq!(world.insert(Vec2::new(2, 3), Cell::Rock), None);
+	assert_eq!(world.bounds(), Some((Vec2::new(-1, -1), Vec2::new(2, 3))));
+	assert_eq!(world.to_string(), "o....\n.#...\n..o..\n....#\n");
+
+	let around_origin = world.neighbours8(Vec2::new(0, 0)).collect::<Vec<_>>();
+	assert_eq!(
+		around_origin,
+		vec![
+			(Vec2::new(1, 1), &Cell::Sand),
+			(Vec2::new(-1, -1), &Cell::Sand)
+		]
+	);
+	assert_eq!(world.neighbours4(Vec2::new(0, 0)).count(), 0);
+	assert_eq!(world.find_iter(Cell::Sand).count(), 2);
+
+	assert_eq!(world.remove(Vec2::new(2, 3)), Some(Cell::Rock));
+	assert_eq!(world.remove(above), Some(Cell::Sand));
+	assert_eq!(world.bounds(), Some((Vec2::new(0, 0), Vec2::new(1, 1))));
+	assert_eq!(world.to_string(), "#.\n.o\n");
+
+	world.remove(Vec2::new(0, 0));
+	world.remove(Vec2::new(1, 1));
+	assert!(world.is_empty());
+	assert_eq!(world.ray(Vec2::new(0, 0), TravelDirection::E).count(), 0);
+	assert_eq!(world.bounds(), None);
+	assert_eq!(world.to_string(), "");
+}
+
+#[test]
+fn sparse_grid_queries_match_fbgrid() {
+	use crate::testing::Letter;
+
+	let dense = FBGrid::<Letter>::parse("a.b.\n.c..\nd..e\n..f.").unwrap();
+	let sparse = SparseGrid::from(dense.clone());
+	// The dense answers, minus the blanks the sparse grid doesn't store
+	let occupied = |cells: Vec<((usize, usize), &Letter)>| {
+		cells
+			.into_iter()
+			.filter(|(_, val)| **val != Letter::Blank)
+			.map(|(coord, val)| (Vec2::from(coord), val.clone()))
+			.collect::<Vec<_>>()
+	};
+	let stored = |cells: Vec<(Vec2, &Letter)>| {
+		cells
+			.into_iter()
+			.map(|(pos, val)| (pos, val.clone()))
+			.collect::<Vec<_>>()
+	};
+
+	assert_eq!(
+		stored(sparse.ring(Vec2::new(1, 1), 1).collect()),
+		occupied(dense.ring((1, 1), 1).collect())
+	);
+	for dir in TravelDirection::all() {
+		assert_eq!(
+			stored(sparse.ray(Vec2::new(1, 1), dir).collect()),
+			occupied(dense.ray((1, 1), dir).collect()),
+			"{}",
+			dir
+		);
+	}
+	assert_eq!(
+		stored(sparse.line(Vec2::new(0, 0), Vec2::new(3, 2)).collect()),
+		occupied(dense.line((0, 0), (3, 2)).collect())
+	);
+
+	// Rays starting outside the occupied cells still find them, then stop
+	let from_above = sparse.ray(Vec2::new(-5, 2), TravelDirection::S);
+	assert_eq!(
+		stored(from_above.collect()),
+		vec![
+			(Vec2::new(0, 2), Letter::Letter('b')),
+			(Vec2::new(3, 2), Letter::Letter('f'))
+		]
+	);
+	assert_eq!(sparse.ray(Vec2::new(-5, 2), TravelDirection::N).count(), 0);
+	assert_eq!(sparse.ray_by(Vec2::new(1, 1), Vec2::default()).count(), 1);
+}
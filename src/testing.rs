@@ -0,0 +1,46 @@
+//! Helpers shared by the tests in the puzzle files and the grid modules
+
+use itertools::Itertools;
+use proptest::prelude::*;
+
+use crate::{FBGrid, GridCell};
+
+mod snapshot;
+pub use snapshot::assert_snapshot;
+
+/// Random rectangular grids (1-8 rows & columns) drawn from `cells`, rows joined by `\n`
+pub fn grid_text(cells: &'static str) -> impl Strategy<Value = String> {
+	let cells = cells.chars().collect::<Vec<_>>();
+	(1..=8usize, 1..=8usize).prop_flat_map(move |(rows, cols)| {
+		proptest::collection::vec(proptest::sample::select(cells.clone()), rows * cols).prop_map(
+			move |grid| {
+				grid.chunks(cols)
+					.map(|row| row.iter().collect::<String>())
+					.join("\n")
+			},
+		)
+	})
+}
+
+/// Checks random grids drawn from `cells` print exactly as they were read, and that the
+/// printed text parses back to the same grid
+pub fn check_grid_round_trip<T>(cells: &'static str)
+where
+	T: GridCell + Default + PartialEq,
+{
+	proptest!(|(text in grid_text(cells))| {
+		let printed = FBGrid::<T>::parse(&text).unwrap().to_string();
+		prop_assert_eq!(&printed, &format!("{}\n", text));
+		prop_assert_eq!(FBGrid::<T>::parse(&printed).unwrap().to_string(), printed);
+	});
+}
+
+/// A cell for grid tests: any letter, or `.` for a blank
+#[derive(Debug, Clone, Default, PartialEq, macros::GridCell)]
+pub enum Letter {
+	#[default]
+	#[cell('.')]
+	Blank,
+	#[cell(matching = char::is_ascii_alphabetic)]
+	Letter(char),
+}
@@ -0,0 +1,60 @@
+//! Snapshot assertions for text renderings: the expected output lives in
+//! `tests/snapshots/<name>.snap`, and mismatches print a coloured line diff.
+//! Run with `UPDATE_SNAPSHOTS=1 cargo test` to accept new or changed renderings.
+
+use std::path::PathBuf;
+
+use similar::{ChangeTag, TextDiff};
+
+const UPDATE_ENV_VAR: &str = "UPDATE_SNAPSHOTS";
+
+fn snapshot_path(name: &str) -> PathBuf {
+	PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+		.join("tests")
+		.join("snapshots")
+		.join(format!("{}.snap", name))
+}
+
+fn updating() -> bool {
+	std::env::var(UPDATE_ENV_VAR).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Unified-style diff, red for lines only in the snapshot and green for lines only in `actual`
+fn coloured_diff(expected: &str, actual: &str) -> String {
+	TextDiff::from_lines(expected, actual)
+		.iter_all_changes()
+		.map(|change| {
+			let (sign, colour) = match change.tag() {
+				ChangeTag::Delete => ('-', "\x1b[31m"),
+				ChangeTag::Insert => ('+', "\x1b[32m"),
+				ChangeTag::Equal => (' ', "\x1b[2m"),
+			};
+			let line = change.as_str().unwrap_or_default().trim_end_matches('\n');
+			format!("{}{} {}\x1b[0m\n", colour, sign, line)
+		})
+		.collect()
+}
+
+/// Compares `actual` with the stored snapshot called `name`, or stores it when updating
+pub fn assert_snapshot(name: &str, actual: &str) {
+	let path = snapshot_path(name);
+	if updating() {
+		std::fs::create_dir_all(path.parent().expect("snapshot dir")).unwrap();
+		std::fs::write(&path, actual).unwrap();
+		return;
+	}
+	let Ok(expected) = std::fs::read_to_string(&path) else {
+		panic!(
+			"No snapshot at {:?}, rerun with {}=1 to accept:\n{}",
+			path, UPDATE_ENV_VAR, actual
+		);
+	};
+	if expected != actual {
+		panic!(
+			"Snapshot {:?} doesn't match (- snapshot, + actual), rerun with {}=1 to accept:\n{}",
+			name,
+			UPDATE_ENV_VAR,
+			coloured_diff(&expected, actual)
+		);
+	}
+}
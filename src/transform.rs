@@ -0,0 +1,266 @@
+//! Turning, mirroring & cutting up an `FBGrid`. A `GridView` looks at a rectangle of a grid
+//! in any `Orientation` without copying it; `to_grid` makes the copy when one's needed.
+
+use crate::{FBGrid, GridCell, GridCoord2};
+
+/// One of the eight ways to turn a grid and/or mirror it: mirrored left-to-right first,
+/// then turned clockwise
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Orientation {
+	pub mirrored: bool,
+	pub quarter_turns: u8,
+}
+impl Orientation {
+	pub const IDENTITY: Self = Self::new(false, 0);
+	pub const ROTATE_RIGHT: Self = Self::new(false, 1);
+	pub const ROTATE_HALF: Self = Self::new(false, 2);
+	pub const ROTATE_LEFT: Self = Self::new(false, 3);
+	/// Left-to-right
+	pub const FLIP_HORIZONTAL: Self = Self::new(true, 0);
+	/// Upside down
+	pub const FLIP_VERTICAL: Self = Self::new(true, 2);
+	/// Rows become columns
+	pub const TRANSPOSE: Self = Self::new(true, 3);
+
+	pub const fn new(mirrored: bool, quarter_turns: u8) -> Self {
+		Self {
+			mirrored,
+			quarter_turns: quarter_turns % 4,
+		}
+	}
+	pub fn all() -> Vec<Orientation> {
+		[false, true]
+			.into_iter()
+			.flat_map(|mirrored| (0..4).map(move |turns| Self::new(mirrored, turns)))
+			.collect()
+	}
+	/// The four turns, without mirroring
+	pub fn rotations() -> Vec<Orientation> {
+		(0..4).map(|turns| Self::new(false, turns)).collect()
+	}
+	/// The (rows, cols) of a `rows`×`cols` grid once oriented
+	fn dimensions(&self, rows: usize, cols: usize) -> (usize, usize) {
+		match self.quarter_turns % 2 {
+			0 => (rows, cols),
+			_ => (cols, rows),
+		}
+	}
+	/// Where `coord` of the oriented grid was in the original `rows`×`cols` one
+	fn source(&self, (mut y, mut x): GridCoord2, rows: usize, cols: usize) -> GridCoord2 {
+		let (mut oriented_rows, mut oriented_cols) = self.dimensions(rows, cols);
+		for _ in 0..self.quarter_turns {
+			// Undo one clockwise turn
+			(y, x) = (oriented_cols - 1 - x, y);
+			(oriented_rows, oriented_cols) = (oriented_cols, oriented_rows);
+		}
+		if self.mirrored {
+			x = oriented_cols - 1 - x;
+		}
+		(y, x)
+	}
+}
+
+/// A rectangle of a grid, seen in some orientation
+pub struct GridView<'a, T>
+where
+	T: Default + PartialEq,
+{
+	grid: &'a FBGrid<T>,
+	top_left: GridCoord2,
+	/// Of the rectangle in the underlying grid, before orienting
+	size: (usize, usize),
+	orientation: Orientation,
+}
+impl<'a, T> GridView<'a, T>
+where
+	T: Default + PartialEq,
+{
+	pub fn rows(&self) -> usize {
+		self.orientation.dimensions(self.size.0, self.size.1).0
+	}
+	pub fn cols(&self) -> usize {
+		self.orientation.dimensions(self.size.0, self.size.1).1
+	}
+	/// Where `coord` of the view is in the underlying grid
+	pub fn source(&self, coord: GridCoord2) -> Option<GridCoord2> {
+		if coord.0 >= self.rows() || coord.1 >= self.cols() {
+			return None;
+		}
+		let (y, x) = self.orientation.source(coord, self.size.0, self.size.1);
+		Some((self.top_left.0 + y, self.top_left.1 + x))
+	}
+	pub fn get(&self, coord: GridCoord2) -> Option<&'a T> {
+		let grid = self.grid;
+		Some(&grid.grid[self.source(coord)?])
+	}
+	/// Every cell in the view's reading order, with its coordinate in the view
+	pub fn indexed_iter(&self) -> impl Iterator<Item = (GridCoord2, &'a T)> + '_ {
+		(0..self.rows())
+			.flat_map(move |y| (0..self.cols()).map(move |x| (y, x)))
+			.map(|coord| (coord, self.get(coord).unwrap()))
+	}
+	/// A new grid holding a copy of what's in view
+	pub fn to_grid(&self) -> FBGrid<T>
+	where
+		T: Clone,
+	{
+		let cells = self.indexed_iter().map(|(_, val)| val.clone()).collect();
+		FBGrid::from(grid::Grid::from_vec(cells, self.cols())).with_edges(self.grid.edges())
+	}
+}
+impl<T> std::fmt::Display for GridView<'_, T>
+where
+	T: GridCell + Default + PartialEq,
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for y in 0..self.rows() {
+			let row = (0..self.cols())
+				.map(|x| self.get((y, x)).unwrap().to_char())
+				.collect::<String>();
+			writeln!(f, "{}", row)?;
+		}
+		Ok(())
+	}
+}
+
+impl<T> FBGrid<T>
+where
+	T: Default + PartialEq,
+{
+	/// The whole grid in another orientation
+	pub fn oriented(&self, orientation: Orientation) -> GridView<'_, T> {
+		GridView {
+			grid: self,
+			top_left: (0, 0),
+			size: (self.grid.rows(), self.grid.cols()),
+			orientation,
+		}
+	}
+	/// The `rows`×`cols` rectangle starting at `top_left`, if it fits on the grid
+	pub fn view(
+		&self,
+		top_left: GridCoord2,
+		rows: usize,
+		cols: usize,
+		orientation: Orientation,
+	) -> Option<GridView<'_, T>> {
+		let fits = top_left.0 + rows <= self.grid.rows() && top_left.1 + cols <= self.grid.cols();
+		fits.then_some(GridView {
+			grid: self,
+			top_left,
+			size: (rows, cols),
+			orientation,
+		})
+	}
+	/// Every `rows`×`cols` rectangle of the grid, by its top-left corner in reading order
+	pub fn windows(
+		&self,
+		rows: usize,
+		cols: usize,
+	) -> impl Iterator<Item = (GridCoord2, GridView<'_, T>)> + '_ {
+		let last_y = (self.grid.rows() + 1).saturating_sub(rows);
+		let last_x = (self.grid.cols() + 1).saturating_sub(cols);
+		(0..last_y)
+			.flat_map(move |y| (0..last_x).map(move |x| (y, x)))
+			.map(move |top_left| {
+				let view = self.view(top_left, rows, cols, Orientation::IDENTITY);
+				(top_left, view.unwrap())
+			})
+	}
+	/// Copies `patch` over the grid with its top-left corner at `top_left`, dropping
+	/// whatever hangs off the edges
+	pub fn paste(&mut self, top_left: GridCoord2, patch: &FBGrid<T>)
+	where
+		T: Clone,
+	{
+		for ((y, x), val) in patch.grid.indexed_iter() {
+			if let Some(cell) = self.grid.get_mut(top_left.0 + y, top_left.1 + x) {
+				*cell = val.clone();
+			}
+		}
+	}
+}
+impl<T> FBGrid<T>
+where
+	T: Clone + Default + PartialEq,
+{
+	pub fn sub_grid(&self, top_left: GridCoord2, rows: usize, cols: usize) -> Option<FBGrid<T>> {
+		Some(
+			self.view(top_left, rows, cols, Orientation::IDENTITY)?
+				.to_grid(),
+		)
+	}
+	pub fn rotated_right(&self) -> FBGrid<T> {
+		self.oriented(Orientation::ROTATE_RIGHT).to_grid()
+	}
+	pub fn rotated_left(&self) -> FBGrid<T> {
+		self.oriented(Orientation::ROTATE_LEFT).to_grid()
+	}
+	pub fn flipped_horizontally(&self) -> FBGrid<T> {
+		self.oriented(Orientation::FLIP_HORIZONTAL).to_grid()
+	}
+	pub fn flipped_vertically(&self) -> FBGrid<T> {
+		self.oriented(Orientation::FLIP_VERTICAL).to_grid()
+	}
+	pub fn transposed(&self) -> FBGrid<T> {
+		self.oriented(Orientation::TRANSPOSE).to_grid()
+	}
+}
+
+#[cfg(test)]
+use crate::testing::Letter;
+
+#[test]
+fn orientations_turn_and_mirror() {
+	let grid = FBGrid::<Letter>::parse("abc\ndef").unwrap();
+	let render = |grid: FBGrid<Letter>| grid.to_string();
+	assert_eq!(render(grid.rotated_right()), "da\neb\nfc\n");
+	assert_eq!(render(grid.rotated_left()), "cf\nbe\nad\n");
+	assert_eq!(render(grid.flipped_horizontally()), "cba\nfed\n");
+	assert_eq!(render(grid.flipped_vertically()), "def\nabc\n");
+	assert_eq!(render(grid.transposed()), "ad\nbe\ncf\n");
+	assert_eq!(
+		grid.oriented(Orientation::ROTATE_HALF).to_string(),
+		"fed\ncba\n"
+	);
+	assert_eq!(
+		grid.oriented(Orientation::new(true, 1)).to_string(),
+		"fc\neb\nda\n"
+	);
+
+	let distinct = Orientation::all()
+		.into_iter()
+		.map(|orientation| grid.oriented(orientation).to_string())
+		.collect::<std::collections::HashSet<_>>();
+	assert_eq!(distinct.len(), 8);
+	assert_eq!(
+		render(grid.rotated_right().rotated_left()),
+		render(grid.clone())
+	);
+	assert_eq!(render(grid.transposed().transposed()), render(grid));
+}
+
+#[test]
+fn sub_grids_windows_and_pasting() {
+	let mut grid = FBGrid::<Letter>::parse("abcd\nefgh\nijkl").unwrap();
+	assert_eq!(grid.sub_grid((1, 1), 2, 2).unwrap().to_string(), "fg\njk\n");
+	assert!(grid.sub_grid((1, 1), 2, 4).is_none());
+
+	let view = grid.view((0, 2), 3, 2, Orientation::ROTATE_RIGHT).unwrap();
+	assert_eq!((view.rows(), view.cols()), (2, 3));
+	assert_eq!(view.to_string(), "kgc\nlhd\n");
+	assert_eq!(view.source((0, 0)), Some((2, 2)));
+	assert_eq!(view.get((2, 0)), None);
+
+	let windows = grid.windows(2, 3).collect::<Vec<_>>();
+	assert_eq!(
+		windows.iter().map(|(at, _)| *at).collect::<Vec<_>>(),
+		vec![(0, 0), (0, 1), (1, 0), (1, 1)]
+	);
+	assert_eq!(windows[3].1.to_string(), "fgh\njkl\n");
+	assert_eq!(grid.windows(4, 1).count(), 0);
+
+	let patch = FBGrid::<Letter>::parse("..\nXY").unwrap();
+	grid.paste((1, 3), &patch);
+	assert_eq!(grid.to_string(), "abcd\nefg.\nijkX\n");
+}